@@ -3,6 +3,7 @@ use std::ops::Add;
 use std::time::Duration;
 use worker::*;
 use atletiek_nu_api::chrono::{NaiveDate, offset, NaiveDateTime, ParseError, DateTime, Utc};
+use atletiek_nu_api::AtnError;
 use urlencoding;
 use console_error_panic_hook;
 
@@ -18,6 +19,16 @@ fn format_http_timestamp(time: DateTime<Utc>) -> String {
     time.format("%a, %d %b %Y %H:%M:%S %Z").to_string()
 }
 
+fn error_response(error: AtnError) -> Result<Response> {
+    console_error!("Error fetching from athletics.app: {}", error);
+    match error {
+        e if e.is_not_found() => Response::error(e.to_string(), 404),
+        e @ AtnError::Unsupported(_) => Response::error(e.to_string(), 501),
+        AtnError::Network(_) | AtnError::HttpStatus { .. } => Response::error("Upstream error", 502),
+        _ => Response::error("Internal error", 500),
+    }
+}
+
 #[event(fetch)]
 async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
     console_error_panic_hook::set_once();
@@ -55,10 +66,7 @@ async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
                     let result = atletiek_nu_api::get_athlete_event_result(id).await;
                     match result {
                         Ok(r) => Response::from_json(&r),
-                        Err(e) => {
-                            console_error!("Error fetching results: {}", e);
-                            Response::error("Internal error", 500)
-                        }
+                        Err(e) => error_response(e)
                     }
                 } else {
                     Response::error("Unable to parse ID", 400)
@@ -79,10 +87,7 @@ async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
                 };
                 match atletiek_nu_api::search_athletes(&query).await {
                     Ok(r) => Response::from_json(&r),
-                    Err(e) =>  {
-                        console_error!("Error fetching results: {}", e);
-                        Response::error("Internal error", 500)
-                    }
+                    Err(e) => error_response(e)
                 }
             } else {
                 Response::error("Missing query", 400)
@@ -94,10 +99,7 @@ async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
                     let result = atletiek_nu_api::get_athlete_profile(id).await;
                     match result {
                         Ok(r) => Response::from_json(&r),
-                        Err(e) => {
-                            console_error!("Error fetching results: {}", e);
-                            Response::error("Internal error", 500)
-                        }
+                        Err(e) => error_response(e)
                     }
                 } else {
                     Response::error("Unable to parse ID", 400)
@@ -111,10 +113,7 @@ async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
                 if let Ok(id) = id.parse::<u32>() {
                     match atletiek_nu_api::get_competition_registrations(&id).await {
                         Ok(r) => Response::from_json(&r),
-                        Err(e) => {
-                            console_error!("Error fetching results: {}", e);
-                            Response::error("Internal error", 500)
-                        }
+                        Err(e) => error_response(e)
                     }
                 } else {
                     Response::error("Unable to parse ID", 400)
@@ -128,10 +127,7 @@ async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
                 if let Ok(id) = id.parse::<u32>() {
                     match atletiek_nu_api::get_competition_registrations_web(&id).await {
                         Ok(r) => Response::from_json(&r),
-                        Err(e) => {
-                            console_error!("Error fetching results: {}", e);
-                            Response::error("Internal error", 500)
-                        }
+                        Err(e) => error_response(e)
                     }
                 } else {
                     Response::error("Unable to parse ID", 400)
@@ -176,10 +172,7 @@ async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
 
                 match atletiek_nu_api::search_competitions_for_time_period(start_date, end_date, &query).await {
                     Ok(r) => Response::from_json(&r),
-                    Err(e) => {
-                        console_error!("Error searching competitions: {}", e);
-                        Response::error("Internal error", 500)
-                    }
+                    Err(e) => error_response(e)
                 }
             } else {
                 Response::error("Internal error", 500)
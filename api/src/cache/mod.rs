@@ -7,6 +7,7 @@ use std::fs::File;
 use std::io::{Read, Write};
 use crate::util::ApiResponse;
 use atletiek_nu_api::chrono::NaiveDate;
+use atletiek_nu_api::{AtnClient, AtnError, CompetitionKind, CompetitionSearch, Country};
 use dashmap::DashMap;
 use log::trace;
 use rocket::request::{FromRequest, Outcome};
@@ -15,7 +16,6 @@ use std::ops::Deref;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use leaky_bucket::RateLimiter;
 use serde::{Serialize, Deserialize};
 use anyhow::Result;
 
@@ -27,6 +27,19 @@ pub enum CachedRequest {
         start: NaiveDate,
         end: NaiveDate,
         query: String,
+        // missing in caches saved before it was added
+        #[serde(default)]
+        country: Option<Country>,
+        /// `None` for the default kinds of the library
+        #[serde(default)]
+        kinds: Option<Vec<CompetitionKind>>,
+        #[serde(default)]
+        results_available: Option<bool>,
+        #[serde(default)]
+        club_members_only: Option<bool>,
+    },
+    GetCompetition {
+        id: u32,
     },
     GetCompetitionRegistrations {
         id: u32,
@@ -34,6 +47,27 @@ pub enum CachedRequest {
     GetCompetitionResults {
         id: u32,
     },
+    GetEventResults {
+        id: u32,
+        event: String,
+    },
+    GetClub {
+        id: u32,
+    },
+    GetClubCompetitionResults {
+        id: u32,
+        competition_id: u32,
+    },
+    GetRelayTeam {
+        id: u32,
+    },
+    GetCompetitionTimetable {
+        id: u32,
+    },
+    GetStartList {
+        id: u32,
+        number: u32,
+    },
     SearchAthletes {
         query: String
     },
@@ -53,6 +87,10 @@ impl CachedRequest {
         start: NaiveDate,
         end: NaiveDate,
         query: Option<String>,
+        country: Option<String>,
+        kinds: Option<String>,
+        results_available: Option<bool>,
+        club_members_only: Option<bool>,
     ) -> Self {
         let query = match query {
             Some(v) => {
@@ -65,7 +103,21 @@ impl CachedRequest {
             None => "".to_string(),
         };
 
-        Self::SearchCompetitions { start, end, query }
+        let country = country.filter(|v| !v.is_empty()).map(Country::from);
+
+        // comma separated codes, unknown ones are ignored
+        let kinds = kinds.filter(|v| !v.is_empty()).map(|v| {
+            let mut kinds: Vec<CompetitionKind> = v.split(',').filter_map(CompetitionKind::from_code).collect();
+            kinds.sort();
+            kinds.dedup();
+            kinds
+        });
+
+        Self::SearchCompetitions { start, end, query, country, kinds, results_available, club_members_only }
+    }
+
+    pub fn new_get_competition(id: u32) -> Self {
+        Self::GetCompetition { id }
     }
 
     pub fn new_get_registrations(id: u32) -> Self {
@@ -75,6 +127,25 @@ impl CachedRequest {
     pub fn new_get_results(id: u32) -> Self {
         Self::GetCompetitionResults { id }
     }
+    pub fn new_get_event_results(id: u32, event: String) -> Self {
+        Self::GetEventResults { id, event }
+    }
+    pub fn new_get_club(id: u32) -> Self {
+        Self::GetClub { id }
+    }
+    pub fn new_get_club_competition_results(id: u32, competition_id: u32) -> Self {
+        Self::GetClubCompetitionResults { id, competition_id }
+    }
+    pub fn new_get_relay_team(id: u32) -> Self {
+        Self::GetRelayTeam { id }
+    }
+    pub fn new_get_timetable(id: u32) -> Self {
+        Self::GetCompetitionTimetable { id }
+    }
+    pub fn new_get_start_list(id: u32, number: u32) -> Self {
+        Self::GetStartList { id, number }
+    }
+
     pub fn new_search_athletes(query: String) -> Self {
         Self::SearchAthletes{ query }
 
@@ -87,47 +158,75 @@ impl CachedRequest {
     fn cache_duration(&self) -> Duration {
         match self {
             Self::SearchCompetitions { .. } => Duration::from_secs(HOUR_IN_S * 12),
+            Self::GetCompetition { .. } => Duration::from_secs(HOUR_IN_S * 12),
             Self::GetCompetitionRegistrations { .. } => Duration::from_secs(HOUR_IN_S * 12),
             Self::GetCompetitionResults { .. } => Duration::from_secs(HOUR_IN_S * 24),
+            Self::GetEventResults { .. } => Duration::from_secs(HOUR_IN_S * 24),
+            Self::GetClub { .. } => Duration::from_secs(HOUR_IN_S * 24),
+            Self::GetClubCompetitionResults { .. } => Duration::from_secs(HOUR_IN_S * 6),
+            Self::GetRelayTeam { .. } => Duration::from_secs(HOUR_IN_S * 12),
+            Self::GetCompetitionTimetable { .. } => Duration::from_secs(HOUR_IN_S * 6),
+            Self::GetStartList { .. } => Duration::from_secs(HOUR_IN_S),
             Self::SearchAthletes { .. } => Duration::from_secs(HOUR_IN_S * 12),
             Self::GetAthleteProfile { .. } => Duration::from_secs(HOUR_IN_S * 12),
         }
     }
 
-    pub async fn run(self, cache: RequestCache, ratelimiter: &State<RateLimiter>) -> ApiResponse {
+    pub async fn run(self, cache: RequestCache, client: &AtnClient) -> ApiResponse {
         if let Some(entry) = cache.lookup(&self) {
             log::info!("Found in cache");
             return ApiResponse::new_ok_from_string(entry.value).cached(entry.timestamp);
         }
 
-        ratelimiter.acquire_one().await;
-
         match match &self {
-            Self::SearchCompetitions { start, end, query } => {
-                atletiek_nu_api::search_competitions_for_time_period(
-                    start.to_owned(),
-                    end.to_owned(),
-                    &query,
-                )
+            Self::SearchCompetitions { start, end, query, country, kinds, results_available, club_members_only } => {
+                let mut search = CompetitionSearch::new(*start, *end).query(query);
+                search.country = country.to_owned();
+                search.results_available = *results_available;
+                search.club_members_only = *club_members_only;
+                if let Some(kinds) = kinds {
+                    search = search.kinds(kinds.iter().copied());
+                }
+
+                client.search_competitions_with(&search)
                 .await
                 .map(|v| rocket::serde::json::to_string(&v).unwrap())
             }
-            Self::GetCompetitionRegistrations { id } => {
-                atletiek_nu_api::get_competition_registrations_web(id)
+            Self::GetCompetition { id } => {
+                client.get_competition(id)
                     .await
                     .map(|v| rocket::serde::json::to_string(&v).unwrap())
             }
-            Self::GetCompetitionResults { id } => match atletiek_nu_api::get_athlete_event_result(*id)
-                .await {
-                Ok(v) => Ok(rocket::serde::json::to_string(&v).unwrap()),
-                Err(e) => return ApiResponse::new_not_found(rocket::serde::json::to_string(&NotFoundError {
-                    error: e.to_string()
-                }).unwrap()),
+            Self::GetCompetitionRegistrations { id } => {
+                client.get_competition_registrations_web(id)
+                    .await
+                    .map(|v| rocket::serde::json::to_string(&v).unwrap())
             }
-            Self::SearchAthletes { query } => atletiek_nu_api::search_athletes(&query)
+            Self::GetCompetitionResults { id } => client.get_athlete_event_result(*id)
+                .await
+                .map(|v| rocket::serde::json::to_string(&v).unwrap()),
+            Self::GetEventResults { id, event } => client.get_event_results(id, event)
                 .await
                 .map(|v| rocket::serde::json::to_string(&v).unwrap()),
-            Self::GetAthleteProfile { id } => atletiek_nu_api::get_athlete_profile(*id)
+            Self::GetClub { id } => client.get_club(*id)
+                .await
+                .map(|v| rocket::serde::json::to_string(&v).unwrap()),
+            Self::GetClubCompetitionResults { id, competition_id } => client.get_club_competition_results(*id, competition_id)
+                .await
+                .map(|v| rocket::serde::json::to_string(&v).unwrap()),
+            Self::GetRelayTeam { id } => client.get_relay_team(*id)
+                .await
+                .map(|v| rocket::serde::json::to_string(&v).unwrap()),
+            Self::GetCompetitionTimetable { id } => client.get_competition_timetable(id)
+                .await
+                .map(|v| rocket::serde::json::to_string(&v).unwrap()),
+            Self::GetStartList { id, number } => client.get_start_list(id, *number)
+                .await
+                .map(|v| rocket::serde::json::to_string(&v).unwrap()),
+            Self::SearchAthletes { query } => client.search_athletes(query)
+                .await
+                .map(|v| rocket::serde::json::to_string(&v).unwrap()),
+            Self::GetAthleteProfile { id } => client.get_athlete_profile(*id)
                 .await
                 .map(|v| rocket::serde::json::to_string(&v).unwrap())
         } {
@@ -135,11 +234,22 @@ impl CachedRequest {
                 cache.insert(self, v.clone());
                 ApiResponse::new_ok_from_string(v).nocache()
             }
-            Err(e) => ApiResponse::new_internal_error(e.to_string()),
+            Err(e) => error_response(e),
         }
     }
 }
 
+fn error_response(error: AtnError) -> ApiResponse {
+    match error {
+        e if e.is_not_found() => ApiResponse::new_not_found(rocket::serde::json::to_string(&NotFoundError {
+            error: e.to_string()
+        }).unwrap()),
+        e @ AtnError::Unsupported(_) => ApiResponse::new_not_implemented(e),
+        e @ (AtnError::Network(_) | AtnError::HttpStatus { .. }) => ApiResponse::new_bad_gateway(e),
+        e => ApiResponse::new_internal_error(e),
+    }
+}
+
 pub struct Cache {
     cached: Arc<DashMap<CachedRequest, CacheEntry>>,
 }
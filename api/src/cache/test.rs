@@ -1,6 +1,25 @@
 use std::ops::Sub;
 use std::time::{Duration, Instant};
-use crate::cache::CacheEntry;
+use atletiek_nu_api::{AtnClient, RateLimit, RetryPolicy};
+use mock_server::{Fault, MockServer};
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+use crate::cache::{Cache, CacheEntry};
+
+const FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../atletiek-nu-api/test-data");
+
+async fn start(rate_limit: Option<RateLimit>) -> (MockServer, Client) {
+    let server = MockServer::start(FIXTURE_DIR).await.unwrap();
+    let atn_client = AtnClient::builder()
+        .base_url(server.url())
+        .retry_policy(RetryPolicy::none())
+        .rate_limit(rate_limit)
+        .build()
+        .unwrap();
+
+    let client = Client::tracked(crate::rocket(Cache::new(), atn_client)).await.unwrap();
+    (server, client)
+}
 
 #[test]
 fn test_cache_entry_serialization() {
@@ -17,4 +36,156 @@ fn test_cache_entry_serialization() {
     let deserialized: CacheEntry = rocket::serde::json::from_str(&serialized).unwrap();
 
     assert_eq!(deserialized.timestamp.duration_since(entry.timestamp).as_secs(), 0);
-}
\ No newline at end of file
+}
+
+#[rocket::async_test]
+async fn test_cache_hit() {
+    let (server, client) = start(None).await;
+
+    let res = client.get("/athletes/profile/921275").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!(res.headers().get_one("X-Cached"), Some("false"));
+    let body = res.into_string().await.unwrap();
+
+    let res = client.get("/athletes/profile/921275").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!(res.headers().get_one("X-Cached"), Some("true"));
+    assert_eq!(res.into_string().await.unwrap(), body);
+
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[rocket::async_test]
+async fn test_ratelimiter() {
+    // the budget starts refilling when the client is built
+    let started = Instant::now();
+    let (server, client) = start(Some(RateLimit::new(1, Duration::from_millis(300)))).await;
+
+    for id in [38406, 38436, 40258] {
+        let res = client.get(format!("/competitions/registrations/{}", id)).dispatch().await;
+        assert_eq!(res.status(), Status::Ok);
+    }
+
+    // the first one goes through right away, the others wait for a refill
+    assert!(started.elapsed() >= Duration::from_millis(600));
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[rocket::async_test]
+async fn test_upstream_error_not_cached() {
+    let (server, client) = start(None).await;
+    server.fault_next(Fault::Status(500));
+
+    let res = client.get("/competitions/results/1793090").dispatch().await;
+    assert_eq!(res.status(), Status::BadGateway);
+
+    let res = client.get("/competitions/results/1793090").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!(res.headers().get_one("X-Cached"), Some("false"));
+
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[rocket::async_test]
+async fn test_competition_details() {
+    let (_server, client) = start(None).await;
+
+    let res = client.get("/competitions/details/38406").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert!(res.into_string().await.unwrap().contains(r#""organiser_club_id":679"#));
+}
+
+#[rocket::async_test]
+async fn test_event_results() {
+    let (_server, client) = start(None).await;
+
+    let res = client.get("/competitions/results/45802/100m").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert!(res.into_string().await.unwrap().contains(r#""status":{"Disqualified":{"rule":"TR16.8"}}"#));
+}
+
+#[rocket::async_test]
+async fn test_club() {
+    let (_server, client) = start(None).await;
+
+    let res = client.get("/clubs/679").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert!(res.into_string().await.unwrap().contains(r#""athlete_id":921275"#));
+
+    let res = client.get("/clubs/679/competitions/38406").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert!(res.into_string().await.unwrap().contains(r#""club_id":679,"competition_id":38406"#));
+}
+
+#[rocket::async_test]
+async fn test_relay_team() {
+    let (_server, client) = start(None).await;
+
+    let res = client.get("/relayteams/9101").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert!(res.into_string().await.unwrap().contains(r#""club_id":679"#));
+}
+
+#[rocket::async_test]
+async fn test_timetable() {
+    let (_server, client) = start(None).await;
+
+    let res = client.get("/competitions/timetable/45802").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert!(res.into_string().await.unwrap().contains(r#""time":"2024-09-15T14:00:00+02:00""#));
+}
+
+#[rocket::async_test]
+async fn test_start_list() {
+    let (_server, client) = start(None).await;
+
+    let res = client.get("/competitions/startlist/45802/1").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert!(res.into_string().await.unwrap().contains(r#""start_group_name":"Women U20""#));
+}
+
+#[rocket::async_test]
+async fn test_not_found() {
+    let (_server, client) = start(None).await;
+
+    let res = client.get("/athletes/profile/1").dispatch().await;
+    assert_eq!(res.status(), Status::NotFound);
+}
+
+#[rocket::async_test]
+async fn test_search_competitions_country() {
+    let (server, client) = start(None).await;
+
+    let res = client.get("/competitions/search?start=2024-06-01&end=2024-06-02&country=be").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    let res = client.get("/competitions/search?start=2024-06-01&end=2024-06-02").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    assert_eq!(res.headers().get_one("X-Cached"), Some("false"));
+
+    let requests = server.requests();
+    assert!(requests[0].contains("country=BE&"));
+    assert!(requests[2].contains("country=NL&"));
+
+    let res = client.get("/competitions/countries").dispatch().await;
+    assert!(res.into_string().await.unwrap().contains(r#"{"code":"BE","name":"Belgium"}"#));
+}
+
+#[rocket::async_test]
+async fn test_search_competitions_kinds() {
+    let (server, client) = start(None).await;
+
+    let res = client.get("/competitions/search?start=2024-06-01&end=2024-06-02&kinds=out,cross&results_available=true").dispatch().await;
+    assert_eq!(res.status(), Status::Ok);
+    let body = res.into_string().await.unwrap();
+    assert!(body.contains(r#""competition_id":38406"#) && !body.contains(r#""competition_id":38436"#));
+    assert!(body.contains(r#""kind":"Outdoor""#));
+
+    // same search, the kinds in another order
+    let res = client.get("/competitions/search?start=2024-06-01&end=2024-06-02&kinds=cross,out&results_available=true").dispatch().await;
+    assert_eq!(res.headers().get_one("X-Cached"), Some("true"));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].contains("event_soort[]=out&"));
+    assert!(requests[1].contains("event_soort[]=cross&"));
+}
@@ -6,11 +6,10 @@ mod util;
 extern crate rocket;
 
 use crate::cache::Cache;
+use atletiek_nu_api::{AtnClient, RateLimit, TracingObserver};
 use log::trace;
-use rocket::tokio;
-use std::sync::mpsc::sync_channel;
+use rocket::{tokio, Build, Rocket};
 use std::time::Duration;
-use leaky_bucket::RateLimiter;
 use self_update::cargo_crate_version;
 use rpr;
 
@@ -60,25 +59,6 @@ async fn main() -> Result<(), rocket::Error> {
     }
 
 
-    tokio::spawn(async {
-        let (req_tx, req_rx) = sync_channel::<(usize, atletiek_nu_api::Request)>(1000);
-        let (sta_tx, sta_rx) = sync_channel::<(usize, atletiek_nu_api::StatusCode)>(1000);
-
-        atletiek_nu_api::set_request_callback(req_tx, sta_tx);
-
-        loop {
-            tokio::time::sleep(Duration::from_millis(50)).await;
-
-            for i in req_rx.try_iter() {
-                println!("REQUEST {} --> {}", i.0, i.1.url().to_string());
-            }
-
-            for i in sta_rx.try_iter() {
-                println!("REQUEST {} --> STATUS {}", i.0, i.1);
-            }
-        }
-    });
-
     let cache = match Cache::load_from_disk() {
         Ok(c) => {
             println!("Cache loaded from disk OK, cleaning");
@@ -105,27 +85,15 @@ async fn main() -> Result<(), rocket::Error> {
         }
     });
 
-    let ratelimiter = RateLimiter::builder()
-        .initial(0)
-        .refill(RATELIMIT_REFIL_AMOUNT as _)
-        .interval(RATELIMIT_REFIL_INTERVAL)
-        .max(2)
-        .build();
+    let client = AtnClient::builder()
+        .from_env()
+        // no burst, one request per refill like the leaky bucket this replaced
+        .rate_limit(Some(RateLimit::new(RATELIMIT_REFIL_AMOUNT as _, RATELIMIT_REFIL_INTERVAL).burst(1)))
+        .observer(TracingObserver)
+        .build()
+        .expect("Failed to build client");
 
-    let cache_managed = cache.clone();
-    rocket::build()
-        .mount(
-            "/",
-            routes![
-                route::search_competitions,
-                route::get_registrations,
-                route::get_results,
-                route::search_athletes,
-                route::get_athlete_profile,
-            ],
-        )
-        .manage(cache_managed)
-        .manage(ratelimiter)
+    rocket(cache.clone(), client)
         .ignite()
         .await?
         .launch()
@@ -138,3 +106,27 @@ async fn main() -> Result<(), rocket::Error> {
 
     Ok(())
 }
+
+fn rocket(cache: Cache, client: AtnClient) -> Rocket<Build> {
+    rocket::build()
+        .mount(
+            "/",
+            routes![
+                route::search_competitions,
+                route::get_countries,
+                route::get_competition,
+                route::get_registrations,
+                route::get_results,
+                route::get_event_results,
+                route::get_timetable,
+                route::get_relay_team,
+                route::get_club,
+                route::get_club_competition_results,
+                route::get_start_list,
+                route::search_athletes,
+                route::get_athlete_profile,
+            ],
+        )
+        .manage(cache)
+        .manage(client)
+}
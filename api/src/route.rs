@@ -1,44 +1,121 @@
-use leaky_bucket::RateLimiter;
+use atletiek_nu_api::{AtnClient, Country};
+use serde::Serialize;
 use rocket::State;
 use crate::cache::{CachedRequest, RequestCache};
 use crate::util::{ApiResponse, RequestNaiveDate};
 
-#[get("/competitions/search?<start>&<end>&<query>")]
+#[derive(FromForm)]
+pub struct SearchFilters {
+    /// Comma separated, e.g. `in,out`
+    kinds: Option<String>,
+    results_available: Option<bool>,
+    club_members_only: Option<bool>,
+}
+
+#[get("/competitions/search?<start>&<end>&<query>&<country>&<filters..>")]
 pub async fn search_competitions(
     start: RequestNaiveDate,
     end: RequestNaiveDate,
     query: Option<String>,
+    country: Option<String>,
+    filters: SearchFilters,
     cache: RequestCache,
-    ratelimiter: &State<RateLimiter>
+    client: &State<AtnClient>,
 ) -> ApiResponse {
-    let req = CachedRequest::new_search_competitions(start.0, end.0, query.clone());
-    req.run(cache, ratelimiter).await
+    let req = CachedRequest::new_search_competitions(
+        start.0,
+        end.0,
+        query.clone(),
+        country,
+        filters.kinds,
+        filters.results_available,
+        filters.club_members_only,
+    );
+    req.run(cache, client).await
+}
+
+#[derive(Serialize)]
+struct CountryInfo {
+    code: &'static str,
+    name: &'static str,
+}
+
+#[get("/competitions/countries")]
+pub fn get_countries() -> ApiResponse {
+    let countries: Vec<CountryInfo> = Country::supported()
+        .iter()
+        .map(|v| CountryInfo { code: v.code(), name: v.name() })
+        .collect();
+
+    ApiResponse::new_ok(&countries)
+}
+
+#[get("/competitions/details/<id>")]
+pub async fn get_competition(id: u32, cache: RequestCache, client: &State<AtnClient>) -> ApiResponse {
+    let req = CachedRequest::new_get_competition(id);
+    req.run(cache, client).await
 }
 
 #[get("/competitions/registrations/<id>")]
-pub async fn get_registrations(id: u32, cache: RequestCache, ratelimiter: &State<RateLimiter>) -> ApiResponse {
+pub async fn get_registrations(id: u32, cache: RequestCache, client: &State<AtnClient>) -> ApiResponse {
     let req = CachedRequest::new_get_registrations(id);
-    req.run(cache, ratelimiter).await
+    req.run(cache, client).await
 }
 
 #[get("/competitions/results/<id>")]
-pub async fn get_results(id: u32, cache: RequestCache, ratelimiter: &State<RateLimiter>) -> ApiResponse {
+pub async fn get_results(id: u32, cache: RequestCache, client: &State<AtnClient>) -> ApiResponse {
     let req = CachedRequest::new_get_results(id);
-    req.run(cache, ratelimiter).await
+    req.run(cache, client).await
+}
+
+#[get("/competitions/results/<id>/<event>")]
+pub async fn get_event_results(id: u32, event: String, cache: RequestCache, client: &State<AtnClient>) -> ApiResponse {
+    let req = CachedRequest::new_get_event_results(id, event);
+    req.run(cache, client).await
+}
+
+#[get("/clubs/<id>")]
+pub async fn get_club(id: u32, cache: RequestCache, client: &State<AtnClient>) -> ApiResponse {
+    let req = CachedRequest::new_get_club(id);
+    req.run(cache, client).await
+}
+
+#[get("/clubs/<id>/competitions/<competition_id>")]
+pub async fn get_club_competition_results(id: u32, competition_id: u32, cache: RequestCache, client: &State<AtnClient>) -> ApiResponse {
+    let req = CachedRequest::new_get_club_competition_results(id, competition_id);
+    req.run(cache, client).await
+}
+
+#[get("/relayteams/<id>")]
+pub async fn get_relay_team(id: u32, cache: RequestCache, client: &State<AtnClient>) -> ApiResponse {
+    let req = CachedRequest::new_get_relay_team(id);
+    req.run(cache, client).await
+}
+
+#[get("/competitions/timetable/<id>")]
+pub async fn get_timetable(id: u32, cache: RequestCache, client: &State<AtnClient>) -> ApiResponse {
+    let req = CachedRequest::new_get_timetable(id);
+    req.run(cache, client).await
+}
+
+#[get("/competitions/startlist/<id>/<number>")]
+pub async fn get_start_list(id: u32, number: u32, cache: RequestCache, client: &State<AtnClient>) -> ApiResponse {
+    let req = CachedRequest::new_get_start_list(id, number);
+    req.run(cache, client).await
 }
 
 #[get("/athletes/search/<query>")]
 pub async fn search_athletes(
     query: String,
     cache: RequestCache,
-    ratelimiter: &State<RateLimiter>,
+    client: &State<AtnClient>,
 ) -> ApiResponse {
     let req = CachedRequest::new_search_athletes(query.clone());
-    req.run(cache, ratelimiter).await
+    req.run(cache, client).await
 }
 
 #[get("/athletes/profile/<id>")]
-pub async fn get_athlete_profile(id: u32, cache: RequestCache, ratelimiter: &State<RateLimiter>) -> ApiResponse {
+pub async fn get_athlete_profile(id: u32, cache: RequestCache, client: &State<AtnClient>) -> ApiResponse {
     let req = CachedRequest::new_get_athlete_profile(id);
-    req.run(cache, ratelimiter).await
+    req.run(cache, client).await
 }
@@ -44,7 +44,10 @@ pub enum ApiResponse {
         headers: Vec<(String, String)>,
     },
     InternalError(String),
-    NotFound(String)
+    NotFound(String),
+    // athletics.app failed or couldn't be reached
+    BadGateway(String),
+    NotImplemented(String),
 }
 
 impl ApiResponse {
@@ -90,6 +93,20 @@ impl ApiResponse {
         Self::NotFound(error.to_string())
     }
 
+    pub fn new_bad_gateway<T>(error: T) -> Self
+    where
+        T: ToString,
+    {
+        Self::BadGateway(error.to_string())
+    }
+
+    pub fn new_not_implemented<T>(error: T) -> Self
+    where
+        T: ToString,
+    {
+        Self::NotImplemented(error.to_string())
+    }
+
     pub fn nocache(self) -> Self {
         self.add_header("X-Cached", "false")
     }
@@ -106,6 +123,8 @@ impl<'r> Responder<'r, 'static> for ApiResponse {
             Self::Ok { body, .. } => (body, Status::Ok),
             Self::InternalError(body) => (body, Status::InternalServerError),
             Self::NotFound(body) => (body, Status::NotFound),
+            Self::BadGateway(body) => (body, Status::BadGateway),
+            Self::NotImplemented(body) => (body, Status::NotImplemented),
         };
 
         let mut resp = Response::build_from(body.to_owned().respond_to(request)?);
@@ -1,15 +1,15 @@
 use atletiek_nu_api::models::{competitions_list_web, athlete_profile};
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, Criterion};
 use scraper::Html;
 
-const COMPETITIONS_LIST_WEB_SHORT_HTML: &'static str =
-    include_str!("../../test-cli/test-data/feeder-short.html");
+const COMPETITIONS_LIST_WEB_SHORT_HTML: &str =
+    include_str!("../test-data/feeder_short.html");
 
-const COMPETITIONS_LIST_WEB_LONG_HTML: &'static str =
-    include_str!("../../test-cli/test-data/feeder-long.html");
+const COMPETITIONS_LIST_WEB_LONG_HTML: &str =
+    include_str!("../test-data/synthetic_feeder_long.html");
 
-const PROFILE_862577_HTML: &'static str =
-    include_str!("../../test-cli/test-data/profile_862577.html");
+const PROFILE_IMPLEMENTS_HTML: &str =
+    include_str!("../test-data/profile_implements.html");
 
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("competitions_list_web (3 items)", |b| {
@@ -24,9 +24,9 @@ fn criterion_benchmark(c: &mut Criterion) {
         })
     });
 
-    c.bench_function("athlete_profile 862577", |b| {
+    c.bench_function("athlete_profile (implements)", |b| {
         b.iter(|| {
-            athlete_profile::parse(Html::parse_document(PROFILE_862577_HTML))
+            athlete_profile::parse(Html::parse_document(PROFILE_IMPLEMENTS_HTML))
         })
     });
 }
@@ -0,0 +1,340 @@
+//! Blocking versions of the requests, for callers that don't run an async runtime themselves.
+//!
+//! The requests run on a small tokio runtime owned by the [`BlockingClient`], so these functions
+//! must not be called from within an async context.
+
+use std::sync::{Arc, OnceLock};
+use chrono::NaiveDate;
+use tokio::runtime::Runtime;
+use crate::error::{AtnError, Result};
+use crate::models::athlete_event_result::AthleteEventResults;
+use crate::models::athlete_list::AthleteList;
+use crate::models::athlete_profile::AthleteProfile;
+use crate::models::competition_details::CompetitionDetails;
+use crate::models::competitions_list::CompetitionsList;
+use crate::models::competitions_list_web::CompetitionsWebList;
+use crate::models::event_results::EventResults;
+use crate::models::start_list::StartList;
+use crate::models::club::{Club, ClubCompetitionResults};
+use crate::models::relay_team::RelayTeamDetails;
+use crate::models::competition_timetable::CompetitionTimetable;
+use crate::models::registrations_list::RegistrationsList;
+use crate::models::registrations_list_web::RegistrationsWebList;
+use crate::parser::Parsed;
+use crate::search::CompetitionSearch;
+use crate::traits::CompetitionID;
+use crate::AtnClient;
+
+static DEFAULT_CLIENT: OnceLock<BlockingClient> = OnceLock::new();
+
+/// Blocking wrapper around an [`AtnClient`].
+///
+/// Cloning is cheap, all clones share the same client and runtime.
+#[derive(Clone)]
+pub struct BlockingClient {
+    client: AtnClient,
+    runtime: Arc<Runtime>,
+}
+
+impl BlockingClient {
+    /// The connections of `client` end up tied to the runtime of this wrapper,
+    /// so it shouldn't be used from another runtime as well
+    pub fn new(client: AtnClient) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("atn-blocking")
+            .enable_all()
+            .build()
+            .map_err(|e| AtnError::io("Failed to start the blocking runtime", e))?;
+
+        Ok(Self {
+            client,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Shared client used by the free functions in this module, wraps [`AtnClient::shared`]
+    /// so both share one rate limit, its observers and settings
+    pub fn shared() -> &'static BlockingClient {
+        DEFAULT_CLIENT.get_or_init(|| {
+            BlockingClient::new(AtnClient::shared().clone()).expect("Failed to build default BlockingClient")
+        })
+    }
+
+    /// Replaces the client used by the free functions in this module.
+    /// Fails if the shared client was already used or set, returning the client.
+    pub fn set_shared(client: BlockingClient) -> std::result::Result<(), BlockingClient> {
+        DEFAULT_CLIENT.set(client)
+    }
+
+    pub fn client(&self) -> &AtnClient {
+        &self.client
+    }
+
+    #[deprecated]
+    #[allow(deprecated)]
+    pub fn search_competitions(&self, q: &str) -> Result<CompetitionsList> {
+        self.runtime.block_on(self.client.search_competitions(q))
+    }
+
+    pub fn search_athletes(&self, q: &str) -> Result<AthleteList> {
+        self.runtime.block_on(self.client.search_athletes(q))
+    }
+
+    #[deprecated(note = "Please use get_competition_registrations_web instead")]
+    #[allow(deprecated)]
+    pub fn get_competition_registrations<C: CompetitionID>(&self, competition_id: &C) -> Result<RegistrationsList> {
+        self.runtime.block_on(self.client.get_competition_registrations(competition_id))
+    }
+
+    pub fn get_competition_registrations_web<C: CompetitionID>(&self, competition_id: &C) -> Result<RegistrationsWebList> {
+        self.runtime.block_on(self.client.get_competition_registrations_web(competition_id))
+    }
+
+    pub fn get_competition_registrations_web_lenient<C: CompetitionID>(
+        &self,
+        competition_id: &C,
+    ) -> Result<Parsed<RegistrationsWebList>> {
+        self.runtime.block_on(self.client.get_competition_registrations_web_lenient(competition_id))
+    }
+
+    pub fn get_competition<C: CompetitionID>(&self, competition_id: &C) -> Result<CompetitionDetails> {
+        self.runtime.block_on(self.client.get_competition(competition_id))
+    }
+
+    pub fn get_competition_lenient<C: CompetitionID>(&self, competition_id: &C) -> Result<Parsed<CompetitionDetails>> {
+        self.runtime.block_on(self.client.get_competition_lenient(competition_id))
+    }
+
+    pub fn get_event_results<C: CompetitionID>(&self, competition_id: &C, event: &str) -> Result<EventResults> {
+        self.runtime.block_on(self.client.get_event_results(competition_id, event))
+    }
+
+    pub fn get_event_results_lenient<C: CompetitionID>(&self, competition_id: &C, event: &str) -> Result<Parsed<EventResults>> {
+        self.runtime.block_on(self.client.get_event_results_lenient(competition_id, event))
+    }
+
+    pub fn get_club(&self, club_id: u32) -> Result<Club> {
+        self.runtime.block_on(self.client.get_club(club_id))
+    }
+
+    pub fn get_club_lenient(&self, club_id: u32) -> Result<Parsed<Club>> {
+        self.runtime.block_on(self.client.get_club_lenient(club_id))
+    }
+
+    pub fn get_club_competition_results<C: CompetitionID>(&self, club_id: u32, competition_id: &C) -> Result<ClubCompetitionResults> {
+        self.runtime.block_on(self.client.get_club_competition_results(club_id, competition_id))
+    }
+
+    pub fn get_relay_team(&self, participant_id: u32) -> Result<RelayTeamDetails> {
+        self.runtime.block_on(self.client.get_relay_team(participant_id))
+    }
+
+    pub fn get_relay_team_lenient(&self, participant_id: u32) -> Result<Parsed<RelayTeamDetails>> {
+        self.runtime.block_on(self.client.get_relay_team_lenient(participant_id))
+    }
+
+    pub fn get_competition_timetable<C: CompetitionID>(&self, competition_id: &C) -> Result<CompetitionTimetable> {
+        self.runtime.block_on(self.client.get_competition_timetable(competition_id))
+    }
+
+    pub fn get_competition_timetable_lenient<C: CompetitionID>(&self, competition_id: &C) -> Result<Parsed<CompetitionTimetable>> {
+        self.runtime.block_on(self.client.get_competition_timetable_lenient(competition_id))
+    }
+
+    pub fn get_start_list<C: CompetitionID>(&self, competition_id: &C, number: u32) -> Result<StartList> {
+        self.runtime.block_on(self.client.get_start_list(competition_id, number))
+    }
+
+    pub fn get_start_list_lenient<C: CompetitionID>(&self, competition_id: &C, number: u32) -> Result<Parsed<StartList>> {
+        self.runtime.block_on(self.client.get_start_list_lenient(competition_id, number))
+    }
+
+    pub fn get_start_list_from_url(&self, url: &str) -> Result<StartList> {
+        self.runtime.block_on(self.client.get_start_list_from_url(url))
+    }
+
+    pub fn get_athlete_event_result(&self, participant_id: u32) -> Result<AthleteEventResults> {
+        self.runtime.block_on(self.client.get_athlete_event_result(participant_id))
+    }
+
+    pub fn get_athlete_event_result_lenient(&self, participant_id: u32) -> Result<Parsed<AthleteEventResults>> {
+        self.runtime.block_on(self.client.get_athlete_event_result_lenient(participant_id))
+    }
+
+    pub fn get_athlete_profile(&self, athlete_id: u32) -> Result<AthleteProfile> {
+        self.runtime.block_on(self.client.get_athlete_profile(athlete_id))
+    }
+
+    pub fn get_athlete_profile_lenient(&self, athlete_id: u32) -> Result<Parsed<AthleteProfile>> {
+        self.runtime.block_on(self.client.get_athlete_profile_lenient(athlete_id))
+    }
+
+    pub fn get_competitions_for_time_period(&self, start: NaiveDate, end: NaiveDate) -> Result<CompetitionsWebList> {
+        self.runtime.block_on(self.client.get_competitions_for_time_period(start, end))
+    }
+
+    pub fn search_competitions_for_time_period(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        q: &str,
+    ) -> Result<CompetitionsWebList> {
+        self.runtime.block_on(self.client.search_competitions_for_time_period(start, end, q))
+    }
+
+    pub fn search_competitions_for_time_period_lenient(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        q: &str,
+    ) -> Result<Parsed<CompetitionsWebList>> {
+        self.runtime.block_on(self.client.search_competitions_for_time_period_lenient(start, end, q))
+    }
+
+    pub fn search_competitions_with(&self, search: &CompetitionSearch) -> Result<CompetitionsWebList> {
+        self.runtime.block_on(self.client.search_competitions_with(search))
+    }
+
+    pub fn search_competitions_with_lenient(&self, search: &CompetitionSearch) -> Result<Parsed<CompetitionsWebList>> {
+        self.runtime.block_on(self.client.search_competitions_with_lenient(search))
+    }
+
+    pub fn search_competitions_range(&self, search: &CompetitionSearch) -> Result<CompetitionsWebList> {
+        self.runtime.block_on(self.client.search_competitions_range(search))
+    }
+
+    pub fn search_competitions_range_lenient(&self, search: &CompetitionSearch) -> Result<Parsed<CompetitionsWebList>> {
+        self.runtime.block_on(self.client.search_competitions_range_lenient(search))
+    }
+}
+
+#[deprecated]
+#[allow(deprecated)]
+pub fn search_competitions(q: &str) -> Result<CompetitionsList> {
+    BlockingClient::shared().search_competitions(q)
+}
+
+pub fn search_athletes(q: &str) -> Result<AthleteList> {
+    BlockingClient::shared().search_athletes(q)
+}
+
+#[deprecated(note = "Please use get_competition_registrations_web instead")]
+#[allow(deprecated)]
+pub fn get_competition_registrations<C: CompetitionID>(competition_id: &C) -> Result<RegistrationsList> {
+    BlockingClient::shared().get_competition_registrations(competition_id)
+}
+
+pub fn get_competition_registrations_web<C: CompetitionID>(competition_id: &C) -> Result<RegistrationsWebList> {
+    BlockingClient::shared().get_competition_registrations_web(competition_id)
+}
+
+pub fn get_competition_registrations_web_lenient<C: CompetitionID>(
+    competition_id: &C,
+) -> Result<Parsed<RegistrationsWebList>> {
+    BlockingClient::shared().get_competition_registrations_web_lenient(competition_id)
+}
+
+pub fn get_competition<C: CompetitionID>(competition_id: &C) -> Result<CompetitionDetails> {
+    BlockingClient::shared().get_competition(competition_id)
+}
+
+pub fn get_competition_lenient<C: CompetitionID>(competition_id: &C) -> Result<Parsed<CompetitionDetails>> {
+    BlockingClient::shared().get_competition_lenient(competition_id)
+}
+
+pub fn get_event_results<C: CompetitionID>(competition_id: &C, event: &str) -> Result<EventResults> {
+    BlockingClient::shared().get_event_results(competition_id, event)
+}
+
+pub fn get_event_results_lenient<C: CompetitionID>(competition_id: &C, event: &str) -> Result<Parsed<EventResults>> {
+    BlockingClient::shared().get_event_results_lenient(competition_id, event)
+}
+
+pub fn get_club(club_id: u32) -> Result<Club> {
+    BlockingClient::shared().get_club(club_id)
+}
+
+pub fn get_club_lenient(club_id: u32) -> Result<Parsed<Club>> {
+    BlockingClient::shared().get_club_lenient(club_id)
+}
+
+pub fn get_club_competition_results<C: CompetitionID>(club_id: u32, competition_id: &C) -> Result<ClubCompetitionResults> {
+    BlockingClient::shared().get_club_competition_results(club_id, competition_id)
+}
+
+pub fn get_relay_team(participant_id: u32) -> Result<RelayTeamDetails> {
+    BlockingClient::shared().get_relay_team(participant_id)
+}
+
+pub fn get_relay_team_lenient(participant_id: u32) -> Result<Parsed<RelayTeamDetails>> {
+    BlockingClient::shared().get_relay_team_lenient(participant_id)
+}
+
+pub fn get_competition_timetable<C: CompetitionID>(competition_id: &C) -> Result<CompetitionTimetable> {
+    BlockingClient::shared().get_competition_timetable(competition_id)
+}
+
+pub fn get_competition_timetable_lenient<C: CompetitionID>(competition_id: &C) -> Result<Parsed<CompetitionTimetable>> {
+    BlockingClient::shared().get_competition_timetable_lenient(competition_id)
+}
+
+pub fn get_start_list<C: CompetitionID>(competition_id: &C, number: u32) -> Result<StartList> {
+    BlockingClient::shared().get_start_list(competition_id, number)
+}
+
+pub fn get_start_list_lenient<C: CompetitionID>(competition_id: &C, number: u32) -> Result<Parsed<StartList>> {
+    BlockingClient::shared().get_start_list_lenient(competition_id, number)
+}
+
+pub fn get_start_list_from_url(url: &str) -> Result<StartList> {
+    BlockingClient::shared().get_start_list_from_url(url)
+}
+
+pub fn get_athlete_event_result(participant_id: u32) -> Result<AthleteEventResults> {
+    BlockingClient::shared().get_athlete_event_result(participant_id)
+}
+
+pub fn get_athlete_event_result_lenient(participant_id: u32) -> Result<Parsed<AthleteEventResults>> {
+    BlockingClient::shared().get_athlete_event_result_lenient(participant_id)
+}
+
+pub fn get_athlete_profile(athlete_id: u32) -> Result<AthleteProfile> {
+    BlockingClient::shared().get_athlete_profile(athlete_id)
+}
+
+pub fn get_athlete_profile_lenient(athlete_id: u32) -> Result<Parsed<AthleteProfile>> {
+    BlockingClient::shared().get_athlete_profile_lenient(athlete_id)
+}
+
+pub fn get_competitions_for_time_period(start: NaiveDate, end: NaiveDate) -> Result<CompetitionsWebList> {
+    BlockingClient::shared().get_competitions_for_time_period(start, end)
+}
+
+pub fn search_competitions_for_time_period(start: NaiveDate, end: NaiveDate, q: &str) -> Result<CompetitionsWebList> {
+    BlockingClient::shared().search_competitions_for_time_period(start, end, q)
+}
+
+pub fn search_competitions_for_time_period_lenient(
+    start: NaiveDate,
+    end: NaiveDate,
+    q: &str,
+) -> Result<Parsed<CompetitionsWebList>> {
+    BlockingClient::shared().search_competitions_for_time_period_lenient(start, end, q)
+}
+
+pub fn search_competitions_with(search: &CompetitionSearch) -> Result<CompetitionsWebList> {
+    BlockingClient::shared().search_competitions_with(search)
+}
+
+pub fn search_competitions_with_lenient(search: &CompetitionSearch) -> Result<Parsed<CompetitionsWebList>> {
+    BlockingClient::shared().search_competitions_with_lenient(search)
+}
+
+pub fn search_competitions_range(search: &CompetitionSearch) -> Result<CompetitionsWebList> {
+    BlockingClient::shared().search_competitions_range(search)
+}
+
+pub fn search_competitions_range_lenient(search: &CompetitionSearch) -> Result<Parsed<CompetitionsWebList>> {
+    BlockingClient::shared().search_competitions_range_lenient(search)
+}
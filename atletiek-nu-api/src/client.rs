@@ -0,0 +1,637 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use log::{info, warn};
+use reqwest::{ClientBuilder, Proxy, StatusCode, Url};
+use scraper::Html;
+use crate::error::{AtnError, Result};
+use crate::models;
+use crate::observer::{RequestInfo, RequestObserver, ResponseInfo, Stopwatch};
+use crate::limit::{Limiter, RateLimit, DEFAULT_MAX_CONCURRENT_REQUESTS};
+use crate::parser::{Parsed, Re};
+use crate::retry::{self, Failure, RetryPolicy};
+use crate::search::{CompetitionSearch, Country, SEARCH_WINDOW_DAYS};
+use crate::models::athlete_event_result::AthleteEventResults;
+use crate::models::athlete_list::AthleteList;
+use crate::models::athlete_profile::AthleteProfile;
+use crate::models::competition_details::CompetitionDetails;
+use crate::models::competitions_list::CompetitionsList;
+use crate::models::competitions_list_web::{CompetitionKind, CompetitionsWebList};
+use crate::models::event_results::EventResults;
+use crate::models::start_list::StartList;
+use crate::models::club::{Club, ClubAthleteResults, ClubCompetitionResults};
+use crate::models::relay_team::RelayTeamDetails;
+use crate::models::competition_timetable::CompetitionTimetable;
+use crate::models::registrations_list::RegistrationsList;
+use crate::models::registrations_list_web::RegistrationsWebList;
+use crate::traits::CompetitionID;
+use crate::transport::{RecordTransport, ReplayTransport, ReqwestTransport, Transport};
+
+pub const DEFAULT_BASE_URL: &str = "https://www.athletics.app";
+// Without this user agent the sortData spans are gone so it needs to be the default
+pub const DEFAULT_USER_AGENT: &str =
+    "Mozilla/5.0 (X11; Linux x86_64; rv:109.0) Gecko/20100101 Firefox/114.0";
+pub const DEFAULT_LANGUAGE: &str = "en_GB";
+pub const DEFAULT_COUNTRY: &str = "NL";
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+// Directory the shared client records responses to
+pub const ENV_RECORD_DIR: &str = "ATN_RECORD_DIR";
+// Directory the shared client replays responses from, instead of using the network
+pub const ENV_REPLAY_DIR: &str = "ATN_REPLAY_DIR";
+
+// 1: competition id, 2: number of the event in the timetable
+const REGEX_START_LIST_URL: &str = r#"wedstrijd/startlijst/([0-9]{1,})/([0-9]{1,})"#;
+
+static DEFAULT_CLIENT: OnceLock<AtnClient> = OnceLock::new();
+// ids of the requests reported to the observers
+static REQUEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Client for the athletics.app website.
+///
+/// Cloning is cheap, all clones share the same connection pool.
+#[derive(Clone)]
+pub struct AtnClient {
+    transport: Arc<dyn Transport>,
+    config: Arc<ClientConfig>,
+}
+
+struct ClientConfig {
+    base_url: String,
+    language: String,
+    country: Country,
+    retry_policy: RetryPolicy,
+    limiter: Limiter,
+    max_concurrent_requests: Option<usize>,
+    observers: Vec<Arc<dyn RequestObserver>>,
+}
+
+pub struct AtnClientBuilder {
+    base_url: String,
+    user_agent: String,
+    timeout: Option<Duration>,
+    proxy: Option<Proxy>,
+    language: String,
+    country: Country,
+    transport: Option<Arc<dyn Transport>>,
+    record_dir: Option<PathBuf>,
+    retry_policy: RetryPolicy,
+    rate_limit: Option<RateLimit>,
+    max_concurrent_requests: Option<usize>,
+    observers: Vec<Arc<dyn RequestObserver>>,
+}
+
+impl Default for AtnClientBuilder {
+    fn default() -> Self {
+        Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            timeout: Some(DEFAULT_TIMEOUT),
+            proxy: None,
+            language: DEFAULT_LANGUAGE.to_string(),
+            country: Country::from_code(DEFAULT_COUNTRY),
+            transport: None,
+            record_dir: None,
+            retry_policy: RetryPolicy::default(),
+            // there's no timer on cloudflare workers to wait for the budget with
+            rate_limit: if cfg!(target_arch = "wasm32") { None } else { Some(RateLimit::default()) },
+            max_concurrent_requests: Some(DEFAULT_MAX_CONCURRENT_REQUESTS),
+            observers: Vec::new(),
+        }
+    }
+}
+
+impl AtnClientBuilder {
+    /// Base url of the site, e.g. `https://www.athletics.app` or a local mirror
+    pub fn base_url<T: ToString>(mut self, base_url: T) -> Self {
+        self.base_url = base_url.to_string().trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn user_agent<T: ToString>(mut self, user_agent: T) -> Self {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Timeout for a whole request, `None` to disable
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Language passed to the app endpoints, e.g. `en_GB`
+    pub fn language<T: ToString>(mut self, language: T) -> Self {
+        self.language = language.to_string();
+        self
+    }
+
+    /// Default country used when searching competitions, an ISO-2 code or a [`Country`]
+    pub fn country<C: Into<Country>>(mut self, country: C) -> Self {
+        self.country = country.into();
+        self
+    }
+
+    /// Use a custom transport instead of sending requests to the live site,
+    /// the user agent, timeout and proxy are ignored in that case
+    pub fn transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Serve all requests from a directory recorded with [`AtnClientBuilder::record_to`]
+    pub fn replay_from<P: Into<PathBuf>>(self, dir: P) -> Self {
+        self.transport(ReplayTransport::new(dir))
+    }
+
+    /// Write every successful response to a directory, keyed by url
+    pub fn record_to<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.record_dir = Some(dir.into());
+        self
+    }
+
+    /// Retries for failed requests, see [`RetryPolicy`]
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Requests per host, shared by all clones of the client. `None` to disable, e.g. for a local mirror.
+    pub fn rate_limit(mut self, rate_limit: Option<RateLimit>) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
+    /// Maximum number of requests in flight at once, shared by all clones of the client. `None` to disable.
+    pub fn max_concurrent_requests(mut self, max_concurrent_requests: Option<usize>) -> Self {
+        self.max_concurrent_requests = max_concurrent_requests;
+        self
+    }
+
+    /// Notifies the observer about every request, can be called multiple times to add more observers
+    pub fn observer<O: RequestObserver + 'static>(mut self, observer: O) -> Self {
+        self.observers.push(Arc::new(observer));
+        self
+    }
+
+    /// Applies [`ENV_REPLAY_DIR`] and [`ENV_RECORD_DIR`] if they are set
+    pub fn from_env(mut self) -> Self {
+        if let Ok(dir) = std::env::var(ENV_REPLAY_DIR) {
+            info!("Replaying requests from {}", dir);
+            self = self.replay_from(dir);
+        }
+
+        if let Ok(dir) = std::env::var(ENV_RECORD_DIR) {
+            info!("Recording requests to {}", dir);
+            self = self.record_to(dir);
+        }
+
+        self
+    }
+
+    pub fn build(self) -> Result<AtnClient> {
+        let mut transport = match self.transport {
+            Some(transport) => transport,
+            None => {
+                let mut builder = ClientBuilder::new().user_agent(self.user_agent);
+
+                // not supported by reqwest on wasm (cloudflare worker)
+                #[cfg(not(target_arch = "wasm32"))]
+                {
+                    if let Some(timeout) = self.timeout {
+                        builder = builder.timeout(timeout);
+                    }
+
+                    if let Some(proxy) = self.proxy {
+                        builder = builder.proxy(proxy);
+                    }
+                }
+
+                Arc::new(ReqwestTransport::new(builder.build()?))
+            }
+        };
+
+        if let Some(dir) = self.record_dir {
+            transport = Arc::new(RecordTransport::new(transport, dir));
+        }
+
+        Ok(AtnClient {
+            transport,
+            config: Arc::new(ClientConfig {
+                base_url: self.base_url,
+                language: self.language,
+                country: self.country,
+                retry_policy: self.retry_policy,
+                limiter: Limiter::new(self.rate_limit, self.max_concurrent_requests),
+                max_concurrent_requests: self.max_concurrent_requests,
+                observers: self.observers,
+            }),
+        })
+    }
+}
+
+impl AtnClient {
+    pub fn builder() -> AtnClientBuilder {
+        AtnClientBuilder::default()
+    }
+
+    /// Shared client used by the free functions in the crate root
+    pub fn shared() -> &'static AtnClient {
+        DEFAULT_CLIENT.get_or_init(|| {
+            AtnClient::builder()
+                .from_env()
+                .build()
+                .expect("Failed to build default AtnClient")
+        })
+    }
+
+    /// Replaces the client used by the free functions, e.g. to add an observer.
+    /// Fails if the shared client was already used or set, returning the client.
+    pub fn set_shared(client: AtnClient) -> std::result::Result<(), AtnClient> {
+        DEFAULT_CLIENT.set(client)
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.config.base_url
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.config.base_url, path)
+    }
+
+    fn notify<F: Fn(&dyn RequestObserver)>(&self, f: F) {
+        for observer in self.config.observers.iter() {
+            f(observer.as_ref());
+        }
+    }
+
+    /// Fetches the page and parses it, reporting both to the observers
+    async fn fetch<T, F: FnOnce(&str) -> Result<T>>(&self, url: &str, parse: F) -> Result<T> {
+        let (request, body) = self.send_request(url).await?;
+        let res = parse(&body);
+
+        match &res {
+            Ok(_) => self.notify(|v| v.on_parse_success(&request)),
+            Err(e) => self.notify(|v| v.on_parse_failure(&request, e)),
+        }
+
+        res
+    }
+
+    async fn send_request(&self, url: &str) -> Result<(RequestInfo, String)> {
+        let parsed_url = Url::parse(url).map_err(|e| AtnError::invalid_url(url, e))?;
+        let mut attempt = 1;
+
+        loop {
+            let permit = self.config.limiter.acquire(&parsed_url).await;
+            let request = RequestInfo {
+                id: REQUEST_COUNTER.fetch_add(1, Ordering::Relaxed),
+                url: parsed_url.clone(),
+                attempt,
+            };
+            self.notify(|v| v.on_start(&request));
+
+            let stopwatch = Stopwatch::start();
+            let res = self.transport.get(url).await;
+            drop(permit);
+
+            let res = match res {
+                Ok(v) => v,
+                Err(e) => {
+                    self.notify(|v| v.on_error(&request, &e));
+
+                    match self.config.retry_policy.retry_delay(attempt, &Failure::Error(&e)) {
+                        Some(delay) => {
+                            warn!("Attempt {} for {} failed ({}), retrying in {}ms", attempt, url, e, delay.as_millis());
+                            self.notify(|v| v.on_retry(&request, delay, &e.to_string()));
+                            retry::sleep(delay).await;
+                            attempt += 1;
+                            continue;
+                        },
+                        None => return Err(e),
+                    }
+                },
+            };
+
+            if let Some(location) = &res.redirected_to {
+                self.notify(|v| v.on_redirect(&request, location));
+            }
+
+            let response = ResponseInfo {
+                status: res.status,
+                bytes: res.body.len(),
+                duration: stopwatch.elapsed(),
+            };
+            self.notify(|v| v.on_response(&request, &response));
+
+            if !res.status.is_success() {
+                let failure = Failure::Status { status: res.status, headers: &res.headers };
+                if let Some(delay) = self.config.retry_policy.retry_delay(attempt, &failure) {
+                    warn!("Attempt {} for {} got status {}, retrying in {}ms", attempt, url, res.status, delay.as_millis());
+                    self.notify(|v| v.on_retry(&request, delay, &format!("HTTP status {}", res.status)));
+                    retry::sleep(delay).await;
+                    attempt += 1;
+                    continue;
+                }
+            }
+
+            return match res.status {
+                StatusCode::NOT_FOUND => Err(AtnError::NotFound { url: url.to_string() }),
+                status if !status.is_success() => Err(AtnError::HttpStatus {
+                    status,
+                    url: url.to_string(),
+                }),
+                _ => Ok((request, res.body)),
+            };
+        }
+    }
+
+    #[deprecated]
+    pub async fn search_competitions(&self, q: &str) -> Result<CompetitionsList> {
+        let url = self.url(&format!("/athleteapp.php?page=events&do=searchresults&country_iso2={}&search={}&predefinedSearchTemplate=0&startDate=-30610225172&endDate=-30610225172&language={}&version=1.16&improvePerformance=0", self.config.country.code(), urlencoding::encode(q), self.config.language));
+        self.fetch(&url, |body| models::competitions_list::parse(Html::parse_fragment(body))).await
+    }
+
+    pub async fn search_athletes(&self, q: &str) -> Result<AthleteList> {
+        let url = self.url(&format!("/athleteapp.php?page=athletes&do=searchresults&name={}&language={}&version=1.16&improvePerformance=0", urlencoding::encode(q), self.config.language));
+        self.fetch(&url, |body| models::athlete_list::parse(Html::parse_fragment(body))).await
+    }
+
+    #[deprecated(note = "Please use get_competition_registrations_web instead")]
+    pub async fn get_competition_registrations<C: CompetitionID>(
+        &self,
+        competition_id: &C,
+    ) -> Result<RegistrationsList> {
+        let url = self.url(&format!("/athleteapp.php?page=event&do=registrations&event_id={}&version=1.16&language={}&improvePerformance=0", competition_id.competition_id(), self.config.language));
+        self.fetch(&url, |body| models::registrations_list::parse(Html::parse_fragment(body))).await
+    }
+
+    pub async fn get_competition_registrations_web<C: CompetitionID>(
+        &self,
+        competition_id: &C,
+    ) -> Result<RegistrationsWebList> {
+        let url = self.url(&format!("/wedstrijd/atleten/{}/", competition_id.competition_id()));
+        self.fetch(&url, |body| models::registrations_list_web::parse(Html::parse_document(body))).await
+    }
+
+    /// Like [`Self::get_competition_registrations_web`], but skips registrations that fail to parse
+    pub async fn get_competition_registrations_web_lenient<C: CompetitionID>(
+        &self,
+        competition_id: &C,
+    ) -> Result<Parsed<RegistrationsWebList>> {
+        let url = self.url(&format!("/wedstrijd/atleten/{}/", competition_id.competition_id()));
+        self.fetch(&url, |body| models::registrations_list_web::parse_lenient(Html::parse_document(body))).await
+    }
+
+    pub async fn get_competition<C: CompetitionID>(&self, competition_id: &C) -> Result<CompetitionDetails> {
+        let url = self.url(&format!("/wedstrijd/main/{}/", competition_id.competition_id()));
+        self.fetch(&url, |body| models::competition_details::parse(Html::parse_document(body))).await
+    }
+
+    /// Like [`Self::get_competition`], but skips the parts of the page that fail to parse
+    pub async fn get_competition_lenient<C: CompetitionID>(&self, competition_id: &C) -> Result<Parsed<CompetitionDetails>> {
+        let url = self.url(&format!("/wedstrijd/main/{}/", competition_id.competition_id()));
+        self.fetch(&url, |body| models::competition_details::parse_lenient(Html::parse_document(body))).await
+    }
+
+    /// All results of `event` at a competition, `event` is the name in [`EventResult::event_url`](crate::models::athlete_event_result::EventResult), e.g. `100m`
+    pub async fn get_event_results<C: CompetitionID>(&self, competition_id: &C, event: &str) -> Result<EventResults> {
+        let url = self.event_results_url(competition_id.competition_id(), event);
+        self.fetch(&url, |body| models::event_results::parse(Html::parse_document(body))).await
+    }
+
+    /// Like [`Self::get_event_results`], but skips results that fail to parse
+    pub async fn get_event_results_lenient<C: CompetitionID>(&self, competition_id: &C, event: &str) -> Result<Parsed<EventResults>> {
+        let url = self.event_results_url(competition_id.competition_id(), event);
+        self.fetch(&url, |body| models::event_results::parse_lenient(Html::parse_document(body))).await
+    }
+
+    fn event_results_url(&self, competition_id: u32, event: &str) -> String {
+        self.url(&format!("/wedstrijd/uitslagenonderdeel/{}/{}/", competition_id, urlencoding::encode(event)))
+    }
+
+    /// A relay team, `participant_id` is the one of [`RelayTeam`](crate::models::registrations_list_web::RelayTeam)
+    pub async fn get_relay_team(&self, participant_id: u32) -> Result<RelayTeamDetails> {
+        let url = self.url(&format!("/estafetteteam/main/{}/", participant_id));
+        self.fetch(&url, |body| models::relay_team::parse(Html::parse_document(body))).await
+    }
+
+    /// Like [`Self::get_relay_team`], but skips members and results that fail to parse
+    pub async fn get_relay_team_lenient(&self, participant_id: u32) -> Result<Parsed<RelayTeamDetails>> {
+        let url = self.url(&format!("/estafetteteam/main/{}/", participant_id));
+        self.fetch(&url, |body| models::relay_team::parse_lenient(Html::parse_document(body))).await
+    }
+
+    /// A club with its members and competitions, the id is in [`RegistrationsWebListElement::club_id`](crate::models::registrations_list_web::RegistrationsWebListElement)
+    pub async fn get_club(&self, club_id: u32) -> Result<Club> {
+        let url = self.url(&format!("/vereniging/main/{}/", club_id));
+        self.fetch(&url, |body| models::club::parse(Html::parse_document(body))).await
+    }
+
+    /// Like [`Self::get_club`], but skips members and competitions that fail to parse
+    pub async fn get_club_lenient(&self, club_id: u32) -> Result<Parsed<Club>> {
+        let url = self.url(&format!("/vereniging/main/{}/", club_id));
+        self.fetch(&url, |body| models::club::parse_lenient(Html::parse_document(body))).await
+    }
+
+    /// The registrations and results of every athlete of a club at a competition, one request per athlete
+    pub async fn get_club_competition_results<C: CompetitionID>(&self, club_id: u32, competition_id: &C) -> Result<ClubCompetitionResults> {
+        let registrations = self.get_competition_registrations_web(competition_id).await?;
+        let athletes = stream::iter(registrations.into_iter().filter(|v| v.club_id == Some(club_id)))
+            .map(|registration| async move {
+                let results = match self.get_athlete_event_result(registration.participant_id).await {
+                    Ok(v) => Some(v),
+                    Err(e) if e.is_not_found() => None,
+                    Err(e) => return Err(e),
+                };
+                Ok(ClubAthleteResults { registration, results })
+            })
+            .buffered(self.concurrency())
+            .try_collect()
+            .await?;
+
+        Ok(ClubCompetitionResults {
+            club_id,
+            competition_id: competition_id.competition_id(),
+            athletes,
+        })
+    }
+
+    /// Every scheduled event of a competition, grouped by day
+    pub async fn get_competition_timetable<C: CompetitionID>(&self, competition_id: &C) -> Result<CompetitionTimetable> {
+        let url = self.url(&format!("/wedstrijd/chronoloog/{}/", competition_id.competition_id()));
+        self.fetch(&url, |body| models::competition_timetable::parse(Html::parse_document(body))).await
+    }
+
+    /// Like [`Self::get_competition_timetable`], but skips events that fail to parse
+    pub async fn get_competition_timetable_lenient<C: CompetitionID>(&self, competition_id: &C) -> Result<Parsed<CompetitionTimetable>> {
+        let url = self.url(&format!("/wedstrijd/chronoloog/{}/", competition_id.competition_id()));
+        self.fetch(&url, |body| models::competition_timetable::parse_lenient(Html::parse_document(body))).await
+    }
+
+    /// Start list of an event in the timetable, `number` is the last part of [`ScheduledEvent::startlist_url`](crate::models::competition_timetable::ScheduledEvent)
+    pub async fn get_start_list<C: CompetitionID>(&self, competition_id: &C, number: u32) -> Result<StartList> {
+        let url = self.url(&format!("/wedstrijd/startlijst/{}/{}/", competition_id.competition_id(), number));
+        self.fetch(&url, |body| models::start_list::parse(Html::parse_document(body))).await
+    }
+
+    /// Like [`Self::get_start_list`], but skips entries that fail to parse
+    pub async fn get_start_list_lenient<C: CompetitionID>(&self, competition_id: &C, number: u32) -> Result<Parsed<StartList>> {
+        let url = self.url(&format!("/wedstrijd/startlijst/{}/{}/", competition_id.competition_id(), number));
+        self.fetch(&url, |body| models::start_list::parse_lenient(Html::parse_document(body))).await
+    }
+
+    /// Start list behind a link on the site, like [`TimetableEvent::startlist_url`](crate::models::athlete_event_result::TimetableEvent)
+    pub async fn get_start_list_from_url(&self, url: &str) -> Result<StartList> {
+        let (competition_id, number) = parse_start_list_url(url)?;
+        self.get_start_list(&competition_id, number).await
+    }
+
+    pub async fn get_athlete_event_result(&self, participant_id: u32) -> Result<AthleteEventResults> {
+        let url = self.url(&format!("/atleet/main/{}/", participant_id));
+        self.fetch(&url, |body| models::athlete_event_result::parse(Html::parse_document(body))).await
+    }
+
+    /// Like [`Self::get_athlete_event_result`], but skips results that fail to parse
+    pub async fn get_athlete_event_result_lenient(&self, participant_id: u32) -> Result<Parsed<AthleteEventResults>> {
+        let url = self.url(&format!("/atleet/main/{}/", participant_id));
+        self.fetch(&url, |body| models::athlete_event_result::parse_lenient(Html::parse_document(body))).await
+    }
+
+    pub async fn get_athlete_profile(&self, athlete_id: u32) -> Result<AthleteProfile> {
+        let url = self.url(&format!("/atleet/profiel/{}", athlete_id));
+        self.fetch(&url, |body| models::athlete_profile::parse(Html::parse_document(body))).await
+    }
+
+    /// Like [`Self::get_athlete_profile`], but skips personal bests and graphs that fail to parse
+    pub async fn get_athlete_profile_lenient(&self, athlete_id: u32) -> Result<Parsed<AthleteProfile>> {
+        let url = self.url(&format!("/atleet/profiel/{}", athlete_id));
+        self.fetch(&url, |body| models::athlete_profile::parse_lenient(Html::parse_document(body))).await
+    }
+
+    pub async fn get_competitions_for_time_period(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<CompetitionsWebList> {
+        self.search_competitions_for_time_period(start, end, "").await
+    }
+
+    pub async fn search_competitions_for_time_period(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        q: &str,
+    ) -> Result<CompetitionsWebList> {
+        self.search_competitions_with(&CompetitionSearch::new(start, end).query(q)).await
+    }
+
+    /// Like [`Self::search_competitions_for_time_period`], but skips competitions that fail to parse
+    pub async fn search_competitions_for_time_period_lenient(
+        &self,
+        start: NaiveDate,
+        end: NaiveDate,
+        q: &str,
+    ) -> Result<Parsed<CompetitionsWebList>> {
+        self.search_competitions_with_lenient(&CompetitionSearch::new(start, end).query(q)).await
+    }
+
+    /// Sends a request for every kind in the search, see [`CompetitionSearch::kinds`]
+    pub async fn search_competitions_with(&self, search: &CompetitionSearch) -> Result<CompetitionsWebList> {
+        let mut list = Vec::new();
+        for kind in search.kinds_to_fetch() {
+            let mut part = self.fetch(&self.competitions_url(search, kind), |body| models::competitions_list_web::parse(Html::parse_document(body))).await?;
+            part.iter_mut().for_each(|v| v.kind = Some(kind));
+            list.extend(part);
+        }
+
+        Ok(search.apply(list))
+    }
+
+    /// Like [`Self::search_competitions_with`], but skips competitions that fail to parse
+    pub async fn search_competitions_with_lenient(&self, search: &CompetitionSearch) -> Result<Parsed<CompetitionsWebList>> {
+        let mut list = Vec::new();
+        let mut warnings = Vec::new();
+        for kind in search.kinds_to_fetch() {
+            let mut part = self.fetch(&self.competitions_url(search, kind), |body| models::competitions_list_web::parse_lenient(Html::parse_document(body))).await?;
+            part.data.iter_mut().for_each(|v| v.kind = Some(kind));
+            list.extend(part.data);
+            warnings.extend(part.warnings);
+        }
+
+        Ok(Parsed {
+            data: search.apply(list),
+            warnings,
+        })
+    }
+
+    /// Searches a period of any length, split into windows of [`SEARCH_WINDOW_DAYS`] days that are sent
+    /// concurrently within the rate limit. The competitions are deduplicated and sorted by date.
+    pub async fn search_competitions_range(&self, search: &CompetitionSearch) -> Result<CompetitionsWebList> {
+        let parts: Vec<CompetitionsWebList> = stream::iter(search.windows(SEARCH_WINDOW_DAYS))
+            .map(|window| async move { self.search_competitions_with(&window).await })
+            .buffered(self.concurrency())
+            .try_collect()
+            .await?;
+
+        Ok(search.apply(parts.into_iter().flatten().collect()))
+    }
+
+    /// Like [`Self::search_competitions_range`], but skips competitions that fail to parse
+    pub async fn search_competitions_range_lenient(&self, search: &CompetitionSearch) -> Result<Parsed<CompetitionsWebList>> {
+        let parts: Vec<Parsed<CompetitionsWebList>> = stream::iter(search.windows(SEARCH_WINDOW_DAYS))
+            .map(|window| async move { self.search_competitions_with_lenient(&window).await })
+            .buffered(self.concurrency())
+            .try_collect()
+            .await?;
+
+        let mut list = Vec::new();
+        let mut warnings = Vec::new();
+        for part in parts {
+            list.extend(part.data);
+            warnings.extend(part.warnings);
+        }
+
+        Ok(Parsed {
+            data: search.apply(list),
+            warnings,
+        })
+    }
+
+    /// Like [`Self::search_competitions_range`], but yields the competitions of every window as soon as they arrive.
+    /// Every window is sorted by date, but the windows can arrive in any order and aren't deduplicated against each other.
+    pub fn search_competitions_stream<'a>(
+        &'a self,
+        search: &CompetitionSearch,
+    ) -> impl Stream<Item = Result<CompetitionsWebList>> + 'a {
+        stream::iter(search.windows(SEARCH_WINDOW_DAYS))
+            .map(move |window| async move { self.search_competitions_with(&window).await })
+            .buffer_unordered(self.concurrency())
+    }
+
+    // how many requests a method that fans out keeps in flight, the limiter still caps them on the wire
+    fn concurrency(&self) -> usize {
+        self.config.max_concurrent_requests.unwrap_or(DEFAULT_MAX_CONCURRENT_REQUESTS).max(1)
+    }
+
+    fn competitions_url(&self, search: &CompetitionSearch, kind: CompetitionKind) -> String {
+        let start = NaiveDateTime::new(search.start, NaiveTime::from_hms_opt(0, 0, 0).unwrap()).and_utc().timestamp();
+        let end = NaiveDateTime::new(search.end, NaiveTime::from_hms_opt(0, 0, 0).unwrap()).and_utc().timestamp();
+        let country = search.country.as_ref().unwrap_or(&self.config.country);
+        self.url(&format!("/feeder.php?page=search&do=events&country={}&event_soort[]={}&search={}&startDate={}&endDate={}", country.code(), kind.code(), urlencoding::encode(&search.query), start, end))
+    }
+}
+
+fn parse_start_list_url(url: &str) -> Result<(u32, u32)> {
+    static RE_START_LIST_URL: OnceLock<Re> = OnceLock::new();
+    RE_START_LIST_URL.get_or_init(|| Re::new(REGEX_START_LIST_URL))
+        .captures(url)
+        .and_then(|v| Some((v[1].parse().ok()?, v[2].parse().ok()?)))
+        .ok_or_else(|| AtnError::invalid_url(url, "not a start list url"))
+}
@@ -0,0 +1,27 @@
+/// Month number of the upper case abbreviations the site uses in dates, e.g. `Sat 01 JUN 2024`
+pub fn month(abbreviation: &str) -> Option<u32> {
+    let month = match abbreviation {
+        "JAN" => 1,
+        "FEB" => 2,
+        "MAR" => 3,
+        "APR" => 4,
+        "MAY" => 5,
+        "JUN" => 6,
+        "JUL" => 7,
+        "AUG" => 8,
+        "SEP" => 9,
+        "OCT" => 10,
+        "NOV" => 11,
+        "DEC" => 12,
+        _ => return None,
+    };
+
+    Some(month)
+}
+
+#[test]
+fn test_month() {
+    assert_eq!(month("JAN"), Some(1));
+    assert_eq!(month("DEC"), Some(12));
+    assert_eq!(month("Dec"), None);
+}
@@ -1 +1,3 @@
+pub mod date;
+pub mod table;
 pub mod wind_speed;
\ No newline at end of file
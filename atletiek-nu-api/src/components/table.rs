@@ -0,0 +1,32 @@
+use scraper::ElementRef;
+use crate::parser::Sel;
+
+/// The header of a table, the site leaves out the columns that don't apply to an event
+pub struct Headers {
+    headers: Vec<String>,
+}
+
+impl Headers {
+    pub fn new(table: ElementRef, header_selector: &Sel) -> Self {
+        Self {
+            headers: table
+                .select(header_selector)
+                .map(|v| v.text().collect::<String>().trim().to_string())
+                .collect(),
+        }
+    }
+
+    /// Index of the first column named one of `names`
+    pub fn find(&self, names: &[&str]) -> Option<usize> {
+        self.headers.iter().position(|v| names.contains(&v.as_str()))
+    }
+}
+
+/// All text in a cell, `None` if it's empty or `-`
+pub fn cell_text(cell: ElementRef) -> Option<String> {
+    let text = cell.text().collect::<String>().trim().to_string();
+    match text.is_empty() || text == "-" {
+        true => None,
+        false => Some(text),
+    }
+}
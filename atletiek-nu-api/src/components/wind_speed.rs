@@ -18,7 +18,13 @@ pub fn parse(text: &str) -> Option<f32> {
                 return None;
             },
         };
-        let value = captures[2].parse::<f32>().unwrap();
+        let value = match captures[2].parse::<f32>() {
+            Ok(v) => v,
+            Err(e) => {
+                warn!("Failed to parse wind speed {}: {}", &captures[2], e);
+                return None;
+            }
+        };
         Some(round_float_to_digits(value, 2) * sign)
     } else {
         warn!("No capture on {}", text);
@@ -32,4 +38,6 @@ pub fn parse(text: &str) -> Option<f32> {
 fn test_wind_speed() {
     assert_eq!(parse("+2.1m/s"), Some(2.1));
     assert_eq!(parse("-1.2 m/s"), Some(-1.2));
+    assert_eq!(parse("+m/s"), None);
+    assert_eq!(parse("+1.2.3m/s"), None);
 }
\ No newline at end of file
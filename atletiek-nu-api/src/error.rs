@@ -0,0 +1,98 @@
+use std::fmt::{Display, Formatter};
+use reqwest::StatusCode;
+
+pub type Result<T> = std::result::Result<T, AtnError>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AtnError {
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("Got HTTP status {status} for {url}")]
+    HttpStatus { status: StatusCode, url: String },
+    #[error("Page not found: {url}")]
+    NotFound { url: String },
+    #[error("No results found! (yet?)")]
+    NoResultsYet,
+    // e.g. Athletics Champs results, which use a different page layout
+    #[error("Not supported: {0}")]
+    Unsupported(String),
+    #[error("Failed to parse {model}{}: {detail}", row_suffix(.row))]
+    Parse {
+        model: &'static str,
+        // index of the table row that failed, if any
+        row: Option<usize>,
+        detail: ParseDetail,
+    },
+    // a malformed url, or one that doesn't point at the page the method expects
+    #[error("Invalid url {url}: {reason}")]
+    InvalidUrl { url: String, reason: String },
+    #[error("{context}: {source}")]
+    Io {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// What went wrong while parsing a page
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseDetail {
+    MissingElement { selector: &'static str },
+    MissingAttribute { attribute: &'static str },
+    MissingText { field: &'static str },
+    RegexMismatch { regex: &'static str, input: String },
+    InvalidValue { field: &'static str, value: String, reason: String },
+}
+
+impl Display for ParseDetail {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingElement { selector } => write!(f, "no element matching '{}'", selector),
+            Self::MissingAttribute { attribute } => write!(f, "missing attribute '{}'", attribute),
+            Self::MissingText { field } => write!(f, "no text for {}", field),
+            Self::RegexMismatch { regex, input } => write!(f, "'{}' did not match '{}'", regex, input),
+            Self::InvalidValue { field, value, reason } => write!(f, "invalid {} '{}': {}", field, value, reason),
+        }
+    }
+}
+
+impl ParseDetail {
+    /// The selector, attribute, regex or field that failed
+    pub fn field(&self) -> &'static str {
+        match self {
+            Self::MissingElement { selector } => selector,
+            Self::MissingAttribute { attribute } => attribute,
+            Self::MissingText { field } => field,
+            Self::RegexMismatch { regex, .. } => regex,
+            Self::InvalidValue { field, .. } => field,
+        }
+    }
+}
+
+fn row_suffix(row: &Option<usize>) -> String {
+    match row {
+        Some(row) => format!(" (row {})", row),
+        None => String::new(),
+    }
+}
+
+impl AtnError {
+    pub(crate) fn io<T: ToString>(context: T, source: std::io::Error) -> Self {
+        Self::Io {
+            context: context.to_string(),
+            source,
+        }
+    }
+
+    pub(crate) fn invalid_url<T: ToString>(url: &str, reason: T) -> Self {
+        Self::InvalidUrl {
+            url: url.to_string(),
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Whether the page (or the requested data on it) doesn't exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::NotFound { .. } | Self::NoResultsYet)
+    }
+}
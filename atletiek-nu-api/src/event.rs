@@ -0,0 +1,271 @@
+use std::fmt::{self, Display, Formatter};
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use crate::parser::Re;
+use crate::performance::PerformanceKind;
+
+// 1: optional number of legs, 2: distance, 3: unit, 4: what kind of race
+const REGEX_RACE: &str = r#"^(?:(\d)x)?(\d+(?:[.,]\d+)?)(m|meters|metres|meter|km|kilometers|kilometer|mile|miles|mijl|engelsemijl)?(h|hurdles|horden|sc|st|steeplechase|steeple|w|walk|snelwandelen|relay|estafette)?$"#;
+
+const MILE_METRES: u32 = 1609;
+
+/// How an event is held
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Discipline {
+    Track,
+    Field,
+    Combined,
+    Relay,
+    Road,
+}
+
+/// One event, whatever way the site spells it
+///
+/// Parses the slugs in the urls (`800m`, `Ver`), the codes in registrations (`60mH`, `SP`, `400m_f`)
+/// and the long names (`60 meters hurdles`, `Shot put`), in English and Dutch.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Event {
+    Run { metres: u32 },
+    Hurdles { metres: u32 },
+    Steeplechase { metres: u32 },
+    RaceWalk { metres: u32 },
+    Road { metres: u32 },
+    Relay { legs: u8, metres: u32 },
+    /// 100, 200, 300 and 400 metres
+    SwedishRelay,
+    HighJump,
+    PoleVault,
+    LongJump,
+    TripleJump,
+    ShotPut,
+    DiscusThrow,
+    JavelinThrow,
+    HammerThrow,
+    BallThrow,
+    /// `None` if the site doesn't say how many, like for a `Meerkamp`
+    Combined { events: Option<u8> },
+    /// Not in the catalogue, as spelled on the site
+    Other(String),
+}
+
+impl Event {
+    pub fn parse(text: &str) -> Self {
+        let text = text.trim();
+        // registrations add the round after an underscore, e.g. `400m_f`
+        let key: String = text
+            .split('_')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .collect();
+
+        match key.as_str() {
+            "hj" | "highjump" | "hoog" | "hoogspringen" => Self::HighJump,
+            "pv" | "polevault" | "polsstok" | "polsstokhoogspringen" => Self::PoleVault,
+            "lj" | "longjump" | "ver" | "verspringen" => Self::LongJump,
+            "tj" | "triplejump" | "hinkstap" | "hinkstapspringen" => Self::TripleJump,
+            "sp" | "shotput" | "kogel" | "kogelstoten" => Self::ShotPut,
+            "dt" | "discus" | "discusthrow" | "discuswerpen" => Self::DiscusThrow,
+            "jt" | "javelin" | "javelinthrow" | "speer" | "speerwerpen" => Self::JavelinThrow,
+            "ht" | "hammer" | "hammerthrow" | "kogelslingeren" | "slingeren" => Self::HammerThrow,
+            "bt" | "ballthrow" | "bal" | "balwerpen" => Self::BallThrow,
+            "swedishrelay" | "zweedseestafette" => Self::SwedishRelay,
+            "marathon" => Self::Road { metres: 42195 },
+            "halfmarathon" | "halvemarathon" => Self::Road { metres: 21097 },
+            "meerkamp" | "combinedevents" => Self::Combined { events: None },
+            "triathlon" | "driekamp" => Self::Combined { events: Some(3) },
+            "tetrathlon" | "vierkamp" => Self::Combined { events: Some(4) },
+            "pentathlon" | "vijfkamp" => Self::Combined { events: Some(5) },
+            "hexathlon" | "zeskamp" => Self::Combined { events: Some(6) },
+            "heptathlon" | "zevenkamp" => Self::Combined { events: Some(7) },
+            "decathlon" | "tienkamp" => Self::Combined { events: Some(10) },
+            _ => match parse_race(&key) {
+                Some(v) => v,
+                None => Self::Other(text.to_string()),
+            },
+        }
+    }
+
+    pub fn discipline(&self) -> Discipline {
+        match self {
+            Self::Run { .. } | Self::Hurdles { .. } | Self::Steeplechase { .. } | Self::RaceWalk { .. } => Discipline::Track,
+            Self::Road { .. } => Discipline::Road,
+            Self::Relay { .. } | Self::SwedishRelay => Discipline::Relay,
+            Self::Combined { .. } => Discipline::Combined,
+            Self::Other(_) => match self.performance_kind() {
+                PerformanceKind::Time => Discipline::Track,
+                PerformanceKind::Points => Discipline::Combined,
+                PerformanceKind::Distance | PerformanceKind::Height => Discipline::Field,
+            },
+            _ => Discipline::Field,
+        }
+    }
+
+    /// What the results are measured in
+    pub fn performance_kind(&self) -> PerformanceKind {
+        match self {
+            Self::HighJump | Self::PoleVault => PerformanceKind::Height,
+            Self::LongJump | Self::TripleJump | Self::ShotPut | Self::DiscusThrow | Self::JavelinThrow | Self::HammerThrow | Self::BallThrow => {
+                PerformanceKind::Distance
+            }
+            Self::Combined { .. } => PerformanceKind::Points,
+            Self::Other(name) => guess_kind(name),
+            _ => PerformanceKind::Time,
+        }
+    }
+
+    /// Sprints up to 200 metres, hurdles up to 110 metres and the horizontal jumps
+    pub fn wind_measured(&self) -> bool {
+        match self {
+            Self::Run { metres } => *metres <= 200,
+            Self::Hurdles { metres } => *metres <= 110,
+            Self::LongJump | Self::TripleJump => true,
+            _ => false,
+        }
+    }
+
+    pub fn lower_is_better(&self) -> bool {
+        self.performance_kind().lower_is_better()
+    }
+}
+
+impl From<&str> for Event {
+    fn from(value: &str) -> Self {
+        Self::parse(value)
+    }
+}
+
+/// The English name, e.g. `60 meters hurdles` or `4 x 100 meters relay`
+impl Display for Event {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Run { metres } => write!(f, "{} meters", metres),
+            Self::Hurdles { metres } => write!(f, "{} meters hurdles", metres),
+            Self::Steeplechase { metres } => write!(f, "{} meters steeplechase", metres),
+            Self::RaceWalk { metres } => write!(f, "{} meters race walk", metres),
+            Self::Road { metres: 42195 } => write!(f, "Marathon"),
+            Self::Road { metres: 21097 } => write!(f, "Half marathon"),
+            Self::Road { metres } if metres % 1000 == 0 => write!(f, "{} km", metres / 1000),
+            Self::Road { metres } => write!(f, "{} meters road", metres),
+            Self::Relay { legs, metres } => write!(f, "{} x {} meters relay", legs, metres),
+            Self::SwedishRelay => write!(f, "Swedish relay"),
+            Self::HighJump => write!(f, "High jump"),
+            Self::PoleVault => write!(f, "Pole vault"),
+            Self::LongJump => write!(f, "Long jump"),
+            Self::TripleJump => write!(f, "Triple jump"),
+            Self::ShotPut => write!(f, "Shot put"),
+            Self::DiscusThrow => write!(f, "Discus throw"),
+            Self::JavelinThrow => write!(f, "Javelin throw"),
+            Self::HammerThrow => write!(f, "Hammer throw"),
+            Self::BallThrow => write!(f, "Ball throw"),
+            Self::Combined { events: Some(3) } => write!(f, "Triathlon"),
+            Self::Combined { events: Some(4) } => write!(f, "Tetrathlon"),
+            Self::Combined { events: Some(5) } => write!(f, "Pentathlon"),
+            Self::Combined { events: Some(6) } => write!(f, "Hexathlon"),
+            Self::Combined { events: Some(7) } => write!(f, "Heptathlon"),
+            Self::Combined { events: Some(10) } => write!(f, "Decathlon"),
+            Self::Combined { events: Some(events) } => write!(f, "Combined event ({} events)", events),
+            Self::Combined { events: None } => write!(f, "Combined events"),
+            Self::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Races like `800m`, `60mH`, `4x100m`, `3000mSC` or `10km`, with the spaces and dashes already taken out
+fn parse_race(key: &str) -> Option<Event> {
+    static RE_RACE: OnceLock<Re> = OnceLock::new();
+    let captures = RE_RACE.get_or_init(|| Re::new(REGEX_RACE)).captures(key)?;
+
+    let distance: f32 = captures[2].replace(',', ".").parse().ok()?;
+    let metres = match captures.get(3).map(|v| v.as_str()) {
+        Some("km" | "kilometers" | "kilometer") => (distance * 1000.0).round() as u32,
+        Some("mile" | "miles" | "mijl" | "engelsemijl") => (distance * MILE_METRES as f32).round() as u32,
+        // without a unit only the relays and races with a kind are clear enough
+        None if captures.get(1).is_none() && captures.get(4).is_none() => return None,
+        _ => distance.round() as u32,
+    };
+
+    if let Some(legs) = captures.get(1) {
+        return Some(Event::Relay { legs: legs.as_str().parse().ok()?, metres });
+    }
+
+    Some(match captures.get(4).map(|v| v.as_str()) {
+        Some("h" | "hurdles" | "horden") => Event::Hurdles { metres },
+        Some("sc" | "st" | "steeplechase" | "steeple") => Event::Steeplechase { metres },
+        Some("w" | "walk" | "snelwandelen") => Event::RaceWalk { metres },
+        Some(_) => return None,
+        None if matches!(captures.get(3).map(|v| v.as_str()), Some("km" | "kilometers" | "kilometer")) => Event::Road { metres },
+        None => Event::Run { metres },
+    })
+}
+
+/// For events that aren't in the catalogue, from the words in the name
+fn guess_kind(name: &str) -> PerformanceKind {
+    let name = name.to_lowercase();
+    let contains = |keywords: &[&str]| keywords.iter().any(|v| name.contains(v));
+
+    if contains(&["kamp", "athlon", "points"]) {
+        PerformanceKind::Points
+    } else if contains(&["high jump", "hoog", "pole vault", "polsstok"]) {
+        PerformanceKind::Height
+    } else if name.starts_with("ver")
+        || contains(&["jump", "hinkstap", "throw", "werpen", "shot", "kogel", "discus", "speer", "javelin", "hammer", "slinger"])
+    {
+        PerformanceKind::Distance
+    } else {
+        PerformanceKind::Time
+    }
+}
+
+#[test]
+fn test_event_spellings() {
+    assert_eq!(Event::parse("800m"), Event::Run { metres: 800 });
+    assert_eq!(Event::parse("800 meters"), Event::Run { metres: 800 });
+    assert_eq!(Event::parse("400m_f"), Event::Run { metres: 400 });
+    assert_eq!(Event::parse("60mH"), Event::Hurdles { metres: 60 });
+    assert_eq!(Event::parse("60 meters hurdles"), Event::Hurdles { metres: 60 });
+    assert_eq!(Event::parse("4x100m"), Event::Relay { legs: 4, metres: 100 });
+    assert_eq!(Event::parse("4 x 100 meters relay"), Event::Relay { legs: 4, metres: 100 });
+    assert_eq!(Event::parse("3000m SC"), Event::Steeplechase { metres: 3000 });
+    assert_eq!(Event::parse("10 km"), Event::Road { metres: 10000 });
+    assert_eq!(Event::parse("Halve marathon"), Event::Road { metres: 21097 });
+    assert_eq!(Event::parse("SP"), Event::ShotPut);
+    assert_eq!(Event::parse("Kogel"), Event::ShotPut);
+    assert_eq!(Event::parse("Shot put"), Event::ShotPut);
+    assert_eq!(Event::parse("Ver"), Event::LongJump);
+    assert_eq!(Event::parse("Discus"), Event::DiscusThrow);
+    assert_eq!(Event::parse("Hoog"), Event::HighJump);
+    assert_eq!(Event::parse("Zweedse estafette"), Event::SwedishRelay);
+    assert_eq!(Event::parse("Zevenkamp"), Event::Combined { events: Some(7) });
+    assert_eq!(Event::parse("Megavortex"), Event::Other("Megavortex".to_string()));
+    assert_eq!(Event::parse("100"), Event::Other("100".to_string()));
+}
+
+#[test]
+fn test_event_metadata() {
+    assert_eq!(Event::parse("100m").discipline(), Discipline::Track);
+    assert!(Event::parse("100m").wind_measured());
+    assert!(!Event::parse("400m").wind_measured());
+    assert!(Event::parse("110mH").wind_measured());
+    assert!(Event::parse("Long jump").wind_measured());
+    assert!(!Event::parse("High jump").wind_measured());
+    assert_eq!(Event::parse("4x400m").discipline(), Discipline::Relay);
+    assert_eq!(Event::parse("Marathon").discipline(), Discipline::Road);
+    assert_eq!(Event::parse("Javelin throw").performance_kind(), PerformanceKind::Distance);
+    assert_eq!(Event::parse("Pole vault").performance_kind(), PerformanceKind::Height);
+    assert_eq!(Event::parse("Decathlon").performance_kind(), PerformanceKind::Points);
+    assert!(Event::parse("1500m").lower_is_better());
+    assert!(!Event::parse("Shot put").lower_is_better());
+    assert_eq!(Event::parse("Weight throw").discipline(), Discipline::Field);
+}
+
+#[test]
+fn test_event_display() {
+    assert_eq!(Event::parse("60mH").to_string(), "60 meters hurdles");
+    assert_eq!(Event::parse("4x100m").to_string(), "4 x 100 meters relay");
+    assert_eq!(Event::parse("Marathon").to_string(), "Marathon");
+    assert_eq!(Event::parse("10km").to_string(), "10 km");
+    assert_eq!(Event::parse("Ver").to_string(), "Long jump");
+}
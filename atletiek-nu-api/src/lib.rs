@@ -1,24 +1,11 @@
-use std::fs::File;
-use std::io::Write;
 use crate::models::competitions_list::CompetitionsList;
-use arc_swap;
-use arc_swap::ArcSwapOption;
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
-use reqwest::ClientBuilder;
-use scraper::Html;
-use std::ops::Deref;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::mpsc::SyncSender;
-use std::sync::Arc;
-use urlencoding;
+use chrono::NaiveDate;
 
 pub use crate::models::athlete_event_result::AthleteEventResults;
 use crate::models::athlete_list::AthleteList;
 use crate::models::registrations_list::RegistrationsList;
 use crate::traits::CompetitionID;
 pub use chrono;
-use log::info;
-use rand::RngCore;
 pub use scraper;
 
 pub const GIT_VERSION: &str = git_version::git_version!();
@@ -27,126 +14,183 @@ pub const GIT_VERSION: &str = git_version::git_version!();
 mod tests;
 
 pub mod models;
+pub mod client;
+pub mod error;
+pub mod transport;
+pub mod retry;
+pub mod limit;
+pub mod observer;
+pub mod search;
+pub mod performance;
+pub mod event;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+mod parser;
 mod traits;
 mod util;
 mod components;
 
 use crate::models::competitions_list_web::CompetitionsWebList;
-pub use reqwest::{Request, StatusCode};
+pub use reqwest::{Proxy, Request, StatusCode, Url};
+pub use crate::client::{AtnClient, AtnClientBuilder};
+pub use crate::error::{AtnError, ParseDetail, Result};
+pub use crate::parser::{ParseWarning, Parsed};
+pub use crate::retry::RetryPolicy;
+pub use crate::search::{CompetitionSearch, Country};
+pub use crate::performance::{Performance, PerformanceKind, ResultStatus};
+pub use crate::event::{Discipline, Event};
+pub use crate::models::competitions_list_web::CompetitionKind;
+pub use crate::limit::RateLimit;
+pub use crate::observer::{RequestInfo, RequestObserver, ResponseInfo};
+#[cfg(feature = "tracing")]
+pub use crate::observer::TracingObserver;
+pub use crate::transport::{RecordTransport, ReplayTransport, ReqwestTransport, Transport, TransportResponse};
 use crate::models::athlete_profile::AthleteProfile;
+use crate::models::competition_details::CompetitionDetails;
+use crate::models::event_results::EventResults;
+use crate::models::start_list::StartList;
+use crate::models::club::{Club, ClubCompetitionResults};
+use crate::models::relay_team::RelayTeamDetails;
+use crate::models::competition_timetable::CompetitionTimetable;
 use crate::models::registrations_list_web::RegistrationsWebList;
 
-static REQUEST_SENDER: ArcSwapOption<SyncSender<(usize, Request)>> = ArcSwapOption::const_empty();
-static STATUS_SENDER: ArcSwapOption<SyncSender<(usize, StatusCode)>> = ArcSwapOption::const_empty();
-static REQUEST_COUNTER: AtomicUsize = AtomicUsize::new(0);
+#[deprecated]
+#[allow(deprecated)]
+pub async fn search_competitions(q: &str) -> Result<CompetitionsList> {
+    AtnClient::shared().search_competitions(q).await
+}
+
+pub async fn search_athletes(q: &str) -> Result<AthleteList> {
+    AtnClient::shared().search_athletes(q).await
+}
 
-pub fn set_request_callback(
-    request_sender: SyncSender<(usize, Request)>,
-    status_sender: SyncSender<(usize, StatusCode)>,
-) {
-    REQUEST_SENDER.store(Some(Arc::new(request_sender)));
-    STATUS_SENDER.store(Some(Arc::new(status_sender)));
+#[deprecated(note = "Please use get_competition_registrations_web instead")]
+#[allow(deprecated)]
+pub async fn get_competition_registrations<C: CompetitionID>(
+    competition_id: &C,
+) -> Result<RegistrationsList> {
+    AtnClient::shared().get_competition_registrations(competition_id).await
 }
 
-pub(crate) async fn send_request(url: &str) -> anyhow::Result<String> {
-    let client = ClientBuilder::new()
-        //.danger_accept_invalid_certs(true)
-        .build()?;
-    let req = client
-        .get(url)
-        // Without this header the sortData spans are gone so it needs to be here
-        .header(
-            "User-Agent",
-            "Mozilla/5.0 (X11; Linux x86_64; rv:109.0) Gecko/20100101 Firefox/114.0",
-        )
-        .build()?;
+pub async fn get_competition_registrations_web<C: CompetitionID>(
+    competition_id: &C,
+) -> Result<RegistrationsWebList> {
+    AtnClient::shared().get_competition_registrations_web(competition_id).await
+}
 
-    let id = REQUEST_COUNTER.fetch_add(1, Ordering::Relaxed);
+pub async fn get_competition_registrations_web_lenient<C: CompetitionID>(
+    competition_id: &C,
+) -> Result<Parsed<RegistrationsWebList>> {
+    AtnClient::shared().get_competition_registrations_web_lenient(competition_id).await
+}
 
-    if let Some(sender) = REQUEST_SENDER.load().deref() {
-        sender.send((id, req.try_clone().unwrap()))?;
-    }
+pub async fn get_competition<C: CompetitionID>(competition_id: &C) -> Result<CompetitionDetails> {
+    AtnClient::shared().get_competition(competition_id).await
+}
 
-    let res = client.execute(req).await?;
+pub async fn get_competition_lenient<C: CompetitionID>(competition_id: &C) -> Result<Parsed<CompetitionDetails>> {
+    AtnClient::shared().get_competition_lenient(competition_id).await
+}
 
-    if let Some(sender) = STATUS_SENDER.load().deref() {
-        sender.send((id, res.status()))?;
-    }
+pub async fn get_event_results<C: CompetitionID>(competition_id: &C, event: &str) -> Result<EventResults> {
+    AtnClient::shared().get_event_results(competition_id, event).await
+}
 
-    let text = res.text().await?;
+pub async fn get_event_results_lenient<C: CompetitionID>(competition_id: &C, event: &str) -> Result<Parsed<EventResults>> {
+    AtnClient::shared().get_event_results_lenient(competition_id, event).await
+}
 
-    if std::env::var("ATN_DUMP_REQ").is_ok() {
-        let n = rand::thread_rng().next_u64();
+pub async fn get_club(club_id: u32) -> Result<Club> {
+    AtnClient::shared().get_club(club_id).await
+}
 
-        let mut file = File::create(format!("/tmp/atn_req_{}.html", n))?;
-        file.write_all(text.as_bytes())?;
-        info!("Dumped request to /tmp/atn_req_{}.html", n);
-    }
+pub async fn get_club_lenient(club_id: u32) -> Result<Parsed<Club>> {
+    AtnClient::shared().get_club_lenient(club_id).await
+}
 
-    Ok(text)
+pub async fn get_club_competition_results<C: CompetitionID>(club_id: u32, competition_id: &C) -> Result<ClubCompetitionResults> {
+    AtnClient::shared().get_club_competition_results(club_id, competition_id).await
 }
 
-#[deprecated]
-pub async fn search_competitions(q: &str) -> anyhow::Result<CompetitionsList> {
-    let url = format!("https://www.athletics.app/athleteapp.php?page=events&do=searchresults&country_iso2=NL&search={}&predefinedSearchTemplate=0&startDate=-30610225172&endDate=-30610225172&language=en_GB&version=1.16&improvePerformance=0", urlencoding::encode(q));
-    let body = send_request(&url).await?;
-    models::competitions_list::parse(Html::parse_fragment(&body))
+pub async fn get_relay_team(participant_id: u32) -> Result<RelayTeamDetails> {
+    AtnClient::shared().get_relay_team(participant_id).await
 }
 
-pub async fn search_athletes(q: &str) -> anyhow::Result<AthleteList> {
-    let url = format!("https://www.athletics.app/athleteapp.php?page=athletes&do=searchresults&name={}&language=en_GB&version=1.16&improvePerformance=0", urlencoding::encode(q));
-    let body = send_request(&url).await?;
-    models::athlete_list::parse(Html::parse_fragment(&body))
+pub async fn get_relay_team_lenient(participant_id: u32) -> Result<Parsed<RelayTeamDetails>> {
+    AtnClient::shared().get_relay_team_lenient(participant_id).await
 }
 
-#[deprecated(note = "Please use get_competition_registrations_web instead")]
-pub async fn get_competition_registrations<C: CompetitionID>(
-    competition_id: &C,
-) -> anyhow::Result<RegistrationsList> {
-    let url = format!("https://www.athletics.app/athleteapp.php?page=event&do=registrations&event_id={}&version=1.16&language=en_GB&improvePerformance=0", competition_id.competition_id());
-    let body = send_request(&url).await?;
-    //println!("{}", body);
-    models::registrations_list::parse(Html::parse_fragment(&body))
+pub async fn get_competition_timetable<C: CompetitionID>(competition_id: &C) -> Result<CompetitionTimetable> {
+    AtnClient::shared().get_competition_timetable(competition_id).await
 }
 
-pub async fn get_competition_registrations_web<C: CompetitionID>(
-    competition_id: &C,
-) -> anyhow::Result<RegistrationsWebList> {
-    let url = format!("https://www.athletics.app/wedstrijd/atleten/{}/", competition_id.competition_id());
-    let body = send_request(&url).await?;
-    models::registrations_list_web::parse(Html::parse_document(&body))
+pub async fn get_competition_timetable_lenient<C: CompetitionID>(competition_id: &C) -> Result<Parsed<CompetitionTimetable>> {
+    AtnClient::shared().get_competition_timetable_lenient(competition_id).await
 }
 
-pub async fn get_athlete_event_result(participant_id: u32) -> anyhow::Result<AthleteEventResults> {
-    let url = format!("https://www.athletics.app/atleet/main/{}/", participant_id);
-    let body = send_request(&url).await?;
-    //std::fs::write("dump.html", &body).unwrap();
-    //panic!("done");
-    //dbg!(&body);
-    models::athlete_event_result::parse(Html::parse_document(&body))
+pub async fn get_start_list<C: CompetitionID>(competition_id: &C, number: u32) -> Result<StartList> {
+    AtnClient::shared().get_start_list(competition_id, number).await
 }
 
-pub async fn get_athlete_profile(athlete_id: u32) -> anyhow::Result<AthleteProfile> {
-    let url = format!("https://www.athletics.app/atleet/profiel/{}", athlete_id);
-    let body = send_request(&url).await?;
-    models::athlete_profile::parse(Html::parse_document(&body))
+pub async fn get_start_list_lenient<C: CompetitionID>(competition_id: &C, number: u32) -> Result<Parsed<StartList>> {
+    AtnClient::shared().get_start_list_lenient(competition_id, number).await
+}
+
+pub async fn get_start_list_from_url(url: &str) -> Result<StartList> {
+    AtnClient::shared().get_start_list_from_url(url).await
+}
+
+pub async fn get_athlete_event_result(participant_id: u32) -> Result<AthleteEventResults> {
+    AtnClient::shared().get_athlete_event_result(participant_id).await
+}
+
+pub async fn get_athlete_event_result_lenient(participant_id: u32) -> Result<Parsed<AthleteEventResults>> {
+    AtnClient::shared().get_athlete_event_result_lenient(participant_id).await
+}
+
+pub async fn get_athlete_profile(athlete_id: u32) -> Result<AthleteProfile> {
+    AtnClient::shared().get_athlete_profile(athlete_id).await
+}
+
+pub async fn get_athlete_profile_lenient(athlete_id: u32) -> Result<Parsed<AthleteProfile>> {
+    AtnClient::shared().get_athlete_profile_lenient(athlete_id).await
 }
 
 pub async fn get_competitions_for_time_period(
     start: NaiveDate,
     end: NaiveDate,
-) -> anyhow::Result<CompetitionsWebList> {
-    search_competitions_for_time_period(start, end, "").await
+) -> Result<CompetitionsWebList> {
+    AtnClient::shared().get_competitions_for_time_period(start, end).await
 }
 
 pub async fn search_competitions_for_time_period(
     start: NaiveDate,
     end: NaiveDate,
     q: &str,
-) -> anyhow::Result<CompetitionsWebList> {
-    let start = NaiveDateTime::new(start, NaiveTime::from_hms_opt(0, 0, 0).unwrap()).and_utc().timestamp();
-    let end = NaiveDateTime::new(end, NaiveTime::from_hms_opt(0, 0, 0).unwrap()).and_utc().timestamp();
-    let url = format!("https://www.athletics.app/feeder.php?page=search&do=events&country=NL&event_soort[]=in&event_soort[]=out&search={}&startDate={}&endDate={}", urlencoding::encode(q), start, end);
-    let body = send_request(&url).await?;
-    models::competitions_list_web::parse(Html::parse_document(&body))
+) -> Result<CompetitionsWebList> {
+    AtnClient::shared().search_competitions_for_time_period(start, end, q).await
+}
+
+pub async fn search_competitions_for_time_period_lenient(
+    start: NaiveDate,
+    end: NaiveDate,
+    q: &str,
+) -> Result<Parsed<CompetitionsWebList>> {
+    AtnClient::shared().search_competitions_for_time_period_lenient(start, end, q).await
+}
+
+pub async fn search_competitions_with(search: &CompetitionSearch) -> Result<CompetitionsWebList> {
+    AtnClient::shared().search_competitions_with(search).await
+}
+
+pub async fn search_competitions_with_lenient(search: &CompetitionSearch) -> Result<Parsed<CompetitionsWebList>> {
+    AtnClient::shared().search_competitions_with_lenient(search).await
+}
+
+pub async fn search_competitions_range(search: &CompetitionSearch) -> Result<CompetitionsWebList> {
+    AtnClient::shared().search_competitions_range(search).await
+}
+
+pub async fn search_competitions_range_lenient(search: &CompetitionSearch) -> Result<Parsed<CompetitionsWebList>> {
+    AtnClient::shared().search_competitions_range_lenient(search).await
 }
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use leaky_bucket::RateLimiter;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use url::Url;
+
+pub const DEFAULT_RATE_LIMIT_REQUESTS: usize = 2;
+pub const DEFAULT_RATE_LIMIT_INTERVAL: Duration = Duration::from_secs(1);
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+
+/// How many requests may be sent to a single host in a given interval.
+///
+/// The budget refills gradually, up to `burst` requests can be sent at once after a quiet period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimit {
+    requests: usize,
+    interval: Duration,
+    burst: usize,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self::new(DEFAULT_RATE_LIMIT_REQUESTS, DEFAULT_RATE_LIMIT_INTERVAL)
+    }
+}
+
+impl RateLimit {
+    /// `requests` per `interval`, with a burst of the same size
+    pub fn new(requests: usize, interval: Duration) -> Self {
+        let requests = requests.max(1);
+
+        Self {
+            requests,
+            interval,
+            burst: requests,
+        }
+    }
+
+    pub fn per_second(requests: usize) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    pub fn burst(mut self, burst: usize) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    fn limiter(&self) -> RateLimiter {
+        RateLimiter::builder()
+            .initial(self.burst)
+            .max(self.burst)
+            .refill(self.requests)
+            .interval(self.interval)
+            .build()
+    }
+}
+
+/// The rate limit budget and concurrency cap shared by all clones of a client
+pub(crate) struct Limiter {
+    rate_limit: Option<RateLimit>,
+    hosts: Mutex<HashMap<String, Arc<RateLimiter>>>,
+    concurrency: Option<Semaphore>,
+}
+
+impl Limiter {
+    pub(crate) fn new(rate_limit: Option<RateLimit>, max_concurrent_requests: Option<usize>) -> Self {
+        Self {
+            rate_limit,
+            hosts: Mutex::new(HashMap::new()),
+            concurrency: max_concurrent_requests.map(|v| Semaphore::new(v.max(1))),
+        }
+    }
+
+    /// Waits until a request to `url` may be sent, the request counts as in flight until the permit is dropped
+    pub(crate) async fn acquire(&self, url: &Url) -> Option<SemaphorePermit<'_>> {
+        if let Some(rate_limit) = &self.rate_limit {
+            let limiter = {
+                let mut hosts = self.hosts.lock().unwrap();
+                let host = url.host_str().unwrap_or_default().to_string();
+                Arc::clone(hosts.entry(host).or_insert_with(|| Arc::new(rate_limit.limiter())))
+            };
+
+            limiter.acquire_one().await;
+        }
+
+        match &self.concurrency {
+            Some(semaphore) => Some(semaphore.acquire().await.expect("Request semaphore is never closed")),
+            None => None,
+        }
+    }
+}
@@ -1,19 +1,28 @@
 use std::collections::HashMap;
-use anyhow::bail;
-use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html};
 use serde::{Deserialize, Serialize};
-use log::{trace, warn};
+use log::trace;
+use crate::error::{AtnError, ParseDetail};
 use crate::models::competition_registrations_list::CompetitionRegistrationList;
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+use crate::performance::{Performance, PerformanceKind, ResultStatus};
+use crate::event::Event;
+
+const MODEL: &'static str = "athlete_event_result";
 
 const REGEX_EVENT: &'static str =
     r#"https://www.athletics.app/wedstrijd/uitslagenonderdeel/[\d]{0,}/([A-z\d-]{0,})/"#;
 const REGEX_COMPETITION_ID: &'static str = r#"wedstrijd/main/([0-9]{0,})/"#;
+const REGEX_HEAT: &'static str = r#"(?i)heat\s*([0-9]{1,})"#;
+const REGEX_LANE: &'static str = r#"(?i)lane\s*([0-9]{1,})"#;
+// a height in the tooltip of a high jump or pole vault, with the attempts at it, e.g. `1,35: xo`
+const REGEX_HEIGHT_ATTEMPTS: &'static str = r#"([0-9]{1,}[,.][0-9]{1,})\s*:?\s*([xXoO-]{1,})"#;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AthleteEventResults {
     pub name: String,
     pub competition_id: u32,
+    /// In the order of the page, with an entry per round if the site shows the rounds, see [`EventResult::round`]
     pub results: Vec<EventResult>,
     pub timetable: Vec<TimetableEvent>,
     pub participated_in: CompetitionRegistrationList,
@@ -23,7 +32,36 @@ pub struct AthleteEventResults {
 pub struct EventResult {
     pub event_name: String,
     pub event_url: String,
+    /// If the site shows the round, e.g. the heats and the final of a 100m are two results of their own
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub round: Option<ResultRound>,
     pub items: Vec<EventResultItem>,
+    /// When this result was achieved, matched on the event in [`AthleteEventResults::timetable`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timetable_event: Option<TimetableEvent>,
+}
+
+impl EventResult {
+    /// From the slug in the url, e.g. `800m`
+    pub fn canonical_event(&self) -> Event {
+        Event::parse(&self.event_name)
+    }
+
+    /// The best of the measurements, leaving out fouls, passes, DNFs and the like
+    pub fn best(&self) -> Option<Performance> {
+        self.items.iter().filter_map(|v| match v {
+            EventResultItem::Measurement { result: Some(result), status: None, .. } => Some(*result),
+            _ => None,
+        }).reduce(|best, v| if v > best { v } else { best })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ResultRound {
+    /// e.g. `Heats` or `Final`
+    pub name: String,
+    pub heat: Option<u16>,
+    pub lane: Option<u16>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +73,25 @@ pub struct TimetableEvent {
     pub start_group_name: String
 }
 
+impl TimetableEvent {
+    /// From the short name, e.g. `60mH`
+    pub fn canonical_event(&self) -> Event {
+        Event::parse(&self.event_short)
+    }
+
+    /// On the canonical event, or the names for events that aren't in the catalogue
+    fn is_event_of(&self, result: &EventResult) -> bool {
+        match (result.canonical_event(), self.canonical_event()) {
+            (Event::Other(_), _) | (_, Event::Other(_)) => {
+                let key = |name: &str| name.to_lowercase().chars().filter(|c| c.is_alphanumeric()).collect::<String>();
+                let name = key(&result.event_name);
+                name == key(&self.event_short) || name == key(&self.event_name)
+            }
+            (event, other) => event == other,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum EventResultItem {
     Position {
@@ -42,11 +99,17 @@ pub enum EventResultItem {
     },
     Measurement {
         wind_speed: Option<f32>,
-        result: f32,
-        dnf: bool,
-        // the reason that it was assumed to be DNF/DNS
+        /// `None` if there's a status instead
+        result: Option<Performance>,
+        /// e.g. a DNF or a foul, from the text or otherwise the `sortData` of the site
         #[serde(skip_serializing_if = "Option::is_none")]
-        dnf_reason: Option<DnfReason>,
+        status: Option<ResultStatus>,
+        /// Which attempt of a jump or throw this is, counting from 1
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        attempt: Option<u8>,
+        /// The heights of a high jump or pole vault, in order
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        heights: Vec<HeightAttempts>,
     },
     Points {
         amount: u16,
@@ -54,11 +117,19 @@ pub enum EventResultItem {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub enum DnfReason {
-    DataBelowZero,
-    DataAboveThreshold {
-        threshold: f32
-    }
+pub struct HeightAttempts {
+    pub height: Performance,
+    pub attempts: Vec<AttemptOutcome>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    /// `o`
+    Cleared,
+    /// `x`
+    Failed,
+    /// `-`
+    Passed,
 }
 
 impl AthleteEventResults {
@@ -87,68 +158,99 @@ impl AthleteEventResults {
 }
 
 /// Expects the DESKTOP site
-pub fn parse(html: Html) -> anyhow::Result<AthleteEventResults> {
-    let selector = Selector::parse("#uitslagentabel > tbody").unwrap();
-    let row_selector = Selector::parse("tr").unwrap();
-    let row_element_selector = Selector::parse("td").unwrap();
-    let a_selector = Selector::parse("a").unwrap();
-    let data_span_selector = Selector::parse("span.sortData").unwrap();
-    let visible_span_selector = Selector::parse("span.tipped").unwrap();
-    let name_element_selector = Selector::parse("div.pageTitle").unwrap();
-    let competition_element_selector = Selector::parse("div#menubottom > a.hidden-xs").unwrap();
-    let visible_xs_inline_selector = Selector::parse("span.visible-xs-inline").unwrap();
-    let hidden_xs_selector = Selector::parse("span.hidden-xs").unwrap();
-
-    let timetable_selector = Selector::parse("table.chronoloogtabel > tbody > tr").unwrap();
-
-    let re_event = Regex::new(&REGEX_EVENT).unwrap();
-    let re_competition_id = Regex::new(&REGEX_COMPETITION_ID).unwrap();
-
-    let name = html.select(&name_element_selector).next().unwrap().text().filter(|v| !v.trim().is_empty()).next().unwrap().trim().to_string().replace("  ", " ");
-    let competition_url = html.select(&competition_element_selector).next().unwrap().value().attr("href").unwrap();
-    let competition_id = re_competition_id.captures_iter(competition_url).next().unwrap()[1].parse().unwrap();
+pub fn parse(html: Html) -> crate::Result<AthleteEventResults> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
 
-    let mut results = Vec::new();
-    let participated_in = super::competition_registrations_list::parse(html.root_element())?;
+/// Expects the DESKTOP site
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<AthleteEventResults>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<AthleteEventResults> {
+    let selector = Sel::new("#uitslagentabel > tbody");
+    let row_selector = Sel::new("tr");
+    let row_element_selector = Sel::new("td");
+    let a_selector = Sel::new("a");
+    let data_span_selector = Sel::new("span.sortData");
+    let visible_span_selector = Sel::new("span.tipped");
+    let name_element_selector = Sel::new("div.pageTitle");
+    let competition_element_selector = Sel::new("div#menubottom > a.hidden-xs");
+    let visible_xs_inline_selector = Sel::new("span.visible-xs-inline");
+    let hidden_xs_selector = Sel::new("span.hidden-xs");
+    let subtext_selector = Sel::new("span.subtext");
+
+    let timetable_selector = Sel::new("table.chronoloogtabel > tbody > tr");
+
+    let re_event = Re::new(REGEX_EVENT);
+    let re_competition_id = Re::new(REGEX_COMPETITION_ID);
+    let re_heat = Re::new(REGEX_HEAT);
+    let re_lane = Re::new(REGEX_LANE);
+    let re_height_attempts = Re::new(REGEX_HEIGHT_ATTEMPTS);
+    let ctx = ParseCtx::new(MODEL);
+
+    let name = ctx.text(ctx.select_first(html.root_element(), &name_element_selector)?, "name")?.replace("  ", " ");
+    let competition_url = ctx.attr(ctx.select_first(html.root_element(), &competition_element_selector)?, "href")?;
+    let competition_id = ctx.value("competition id", &ctx.captures(&re_competition_id, competition_url)?[1])?;
+
+    let participated_in = super::competition_registrations_list::parse_with(html.root_element(), warnings)?;
 
     let table = match html
         .select(&selector)
         .next() {
         Some(v) => v,
-        None => bail!("No results found! (yet?)")
+        None => return Err(AtnError::NoResultsYet)
     };
 
     if table.html().contains("Athletics Champs") {
-        bail!("Athletics champs results not supported yet!");
+        return Err(AtnError::Unsupported("Athletics Champs results".to_string()));
     }
 
-    for row in table.select(&row_selector) {
+    let parse_result_row = |ctx: ParseCtx, row: ElementRef, warnings: &mut Warnings| -> crate::Result<Vec<EventResult>> {
+        let mut results = Vec::new();
         let mut fields = row.select(&row_element_selector);
+        let missing_td = || ctx.error(ParseDetail::MissingElement { selector: row_element_selector.css() });
         //dbg!(row.html());
 
         let mut is_combined_event = false;
         // sometimes there is an extra column for "is combined-event"
         // so, if we cannot find the <a>, try the next one
         // example: https://www.atletiek.nu/atleet/main/1785082/
-        let event = {
-            let mut event_td = fields.next().unwrap();
+        let (event_td, event) = {
+            let event_td = fields.next().ok_or_else(missing_td)?;
             match event_td.select(&a_selector).next() {
-                Some(v) => v,
+                Some(v) => (event_td, v),
                 None => {
                     trace!("is combined-event, using second column for event_td");
                     is_combined_event = true;
-                    event_td = fields.next().unwrap();
-                    event_td.select(&a_selector).next().unwrap()
+                    let event_td = fields.next().ok_or_else(missing_td)?;
+                    (event_td, ctx.select_first(event_td, &a_selector)?)
                 }
             }
         };
 
-        let href = event.value().attr("href").unwrap();
-        let event_name = re_event.captures_iter(href).next().unwrap()[1].to_string();
+        let href = ctx.attr(event, "href")?;
+        let event_name = ctx.captures(&re_event, href)?[1].to_string();
+        let kind = PerformanceKind::from_event(&event_name);
         //dbg!(&event_name);
 
+        // every round is a row of its own, e.g. `Heats, heat 2, lane 4`
+        let round = event_td.select(&subtext_selector).next().map(|v| {
+            let text = v.text().collect::<String>();
+            let number = |re: &Re| re.captures(&text).and_then(|v| v[1].parse().ok());
+            ResultRound {
+                name: text.split(',').next().unwrap_or_default().trim().to_string(),
+                heat: number(&re_heat),
+                lane: number(&re_lane),
+            }
+        });
+
         let fields: Vec<(usize, scraper::ElementRef)> = fields.enumerate().collect();
         let len = fields.len();
+        // the series of a jump or throw has a cell per attempt, before the position and the points
+        let measurement_count = len.saturating_sub(if is_combined_event { 2 } else { 1 });
         for (idx, i) in fields {
             if (idx + 1 == len && !is_combined_event) || (idx + 2 == len && is_combined_event) {
                 // the last one is position, if this isn't a combined-event, otherwise the single-last one is position
@@ -156,13 +258,13 @@ pub fn parse(html: Html) -> anyhow::Result<AthleteEventResults> {
                     Some(v) => match v.parse() {
                         Ok(v) => v,
                         Err(e) => {
-                            warn!("Failed to parse position for event {}: {} ({})", event_name, e, v);
+                            warnings.warn(&ctx, "position", format!("Failed to parse position for event {}: {} ({})", event_name, e, v), &i.html());
                             continue;
                         }
                     },
                     None => {
                         // we don't have a position, maybe DNS/DNF?
-                        warn!("No position for event {}", event_name);
+                        warnings.warn(&ctx, "position", format!("No position for event {}", event_name), &i.html());
                         continue;
                     }
                 };
@@ -170,6 +272,8 @@ pub fn parse(html: Html) -> anyhow::Result<AthleteEventResults> {
                 results.push(EventResult {
                     event_name: event_name.clone(),
                     event_url: href.to_string(),
+                    round: round.clone(),
+                    timetable_event: None,
                     items: vec![EventResultItem::Position {
                         position,
                     }],
@@ -181,16 +285,18 @@ pub fn parse(html: Html) -> anyhow::Result<AthleteEventResults> {
             // if combined-event AND the last one, this is points
             if idx + 1 == len && is_combined_event {
                 let mut items = vec![];
-                match i.text().next().unwrap().parse() {
+                match i.text().next().unwrap_or_default().parse() {
                     Ok(v) => items.push(EventResultItem::Points {
                         amount: v
                     }),
-                    Err(e) => warn!("Failed to parse combined-event points for event {}: {}", event_name, e)
+                    Err(e) => warnings.warn(&ctx, "points", format!("Failed to parse combined-event points for event {}: {}", event_name, e), &i.html())
                 }
 
                 results.push(EventResult {
                     event_name: event_name.clone(),
                     event_url: href.to_string(),
+                    round: round.clone(),
+                    timetable_event: None,
                     items,
                 });
             }
@@ -209,92 +315,150 @@ pub fn parse(html: Html) -> anyhow::Result<AthleteEventResults> {
                 Some(v) => v,
             };
 
-            let data = match data_element.value().attr("data").unwrap().parse() {
+            let data = match ctx.attr(data_element, "data")?.parse() {
                 Ok(v) => v,
                 Err(e) => {
                     trace!("Failed to parse data attr in sortData span: {} ({})", e, data_element.html());
                     continue;
                 }
             };
-            let mut dnf = None;
-            if data < 0.0 {
-                // invalid
-                warn!("Data is less than 0: {:.2} for event {}, assuming DNF/DNS", data, event_name);
-                dnf = Some(DnfReason::DataBelowZero);
-            } else if data > 10000.0 {
-                warn!("Data is more than 10000, assuming DNF/DNS: {:.2} for event {}", data, event_name);
-                dnf = Some(DnfReason::DataAboveThreshold {
-                    threshold: 10000.0
-                });
-            }
-            //dbg!(data);
-
             let wind_speed = crate::components::wind_speed::parse(&visible_element.html());
+            let text = visible_element.text().collect::<String>();
+            let status = ResultStatus::parse(&text).or_else(|| ResultStatus::from_sentinel(kind, data));
+            // the visible text has the precision of a time, the data only as many digits as it needs
+            let result = match status {
+                Some(_) => None,
+                None => Performance::parse(kind, &text).or_else(|| Performance::from_value(kind, data)),
+            };
+            let attempt = match kind != PerformanceKind::Time && measurement_count > 1 {
+                true => Some(idx as u8 + 1),
+                false => None,
+            };
+
+            // the tooltip of a high jump or pole vault has every height, e.g. `1,30: o<br>1,35: xo`
+            let mut heights = Vec::new();
+            if kind == PerformanceKind::Height {
+                for captures in re_height_attempts.captures_iter(visible_element.value().attr("title").unwrap_or_default()) {
+                    let height = match Performance::parse(kind, &captures[1]) {
+                        Some(v) => v,
+                        None => {
+                            warnings.warn(&ctx, "heights", format!("Invalid height {} for event {}", &captures[1], event_name), &visible_element.html());
+                            continue;
+                        }
+                    };
+                    let attempts = captures[2].chars().map(|c| match c {
+                        'o' | 'O' => AttemptOutcome::Cleared,
+                        'x' | 'X' => AttemptOutcome::Failed,
+                        _ => AttemptOutcome::Passed,
+                    }).collect();
+                    heights.push(HeightAttempts { height, attempts });
+                }
+            }
 
             //dbg!(wind_speed);
 
             results.push(EventResult {
                 event_name: event_name.clone(),
                 event_url: href.to_string(),
+                round: round.clone(),
+                timetable_event: None,
                 items: vec![EventResultItem::Measurement {
-                    result: data,
+                    result,
                     wind_speed,
-                    dnf: dnf.is_some(),
-                    dnf_reason: dnf
+                    status,
+                    attempt,
+                    heights,
                 }],
             })
         }
-    }
 
-    let mut res_map: HashMap<String, Vec<EventResult>> = HashMap::new();
+        Ok(results)
+    };
 
-    for i in results {
-        match res_map.contains_key(&i.event_url) {
-            true => { res_map.get_mut(&i.event_url).unwrap().push(i); },
-            false => { res_map.insert(i.event_url.clone(), vec![i]); },
+    let mut results = Vec::new();
+    for (row_idx, row) in table.select(&row_selector).enumerate() {
+        match parse_result_row(ctx.row(row_idx), row, warnings) {
+            Ok(v) => results.extend(v),
+            Err(e) => warnings.skip_row(e, &row.html())?,
         }
     }
 
+    // every cell of a row is a result of its own, so merge those of the same event and round, in the order of the page
     let mut res: Vec<EventResult> = Vec::new();
-    for (_, results) in res_map.into_iter() {
-        let name = results[0].event_name.clone();
-        let url = results[0].event_url.clone();
-        let mut items = Vec::new();
-        for i in results {
-            items.extend(i.items);
+    let mut res_idx: HashMap<(String, Option<ResultRound>), usize> = HashMap::new();
+    for i in results {
+        match res_idx.get(&(i.event_url.clone(), i.round.clone())) {
+            Some(idx) => res[*idx].items.extend(i.items),
+            None => {
+                res_idx.insert((i.event_url.clone(), i.round.clone()), res.len());
+                res.push(i);
+            }
         }
-
-        res.push(EventResult {
-            event_name: name,
-            event_url: url,
-            items
-        })
     }
 
-    let mut timetable = Vec::new();
-    for row in html.select(&timetable_selector) {
-        let row_elements: Vec<_> = row.select(&row_element_selector).collect();
-        if row_elements.len() == 1 {
-            // skip it, it's a multi-day event and row contains only the day.
-            continue;
+    let parse_timetable_row = |ctx: ParseCtx, row_elements: Vec<ElementRef>| -> crate::Result<TimetableEvent> {
+        if row_elements.len() < 3 {
+            return Err(ctx.error(ParseDetail::MissingElement { selector: row_element_selector.css() }));
         }
 
-        let time = chrono::DateTime::from_timestamp(row_elements[0].select(&data_span_selector).next().unwrap().value().attr("data").unwrap().parse().unwrap(), 0).unwrap();
-        let startlist_url = row_elements[0].select(&a_selector).next().unwrap().value().attr("href").unwrap().to_string();
-        let start_group_name = row_elements[1].select(&a_selector).next().unwrap().select(&hidden_xs_selector).next().unwrap().text().next().unwrap().to_string();
-        let event_a = row_elements[2].select(&a_selector).next().unwrap();
-        let event_short = event_a.select(&visible_xs_inline_selector).next().unwrap().text().next().unwrap().to_string();
-        let event_long = event_a.select(&hidden_xs_selector).next().unwrap().text().next().unwrap().to_string();
+        let timestamp_text = ctx.attr(ctx.select_first(row_elements[0], &data_span_selector)?, "data")?;
+        let time = chrono::DateTime::from_timestamp(ctx.value("time", timestamp_text)?, 0)
+            .ok_or_else(|| ctx.invalid("time", timestamp_text, "out of range"))?;
+        let startlist_url = ctx.attr(ctx.select_first(row_elements[0], &a_selector)?, "href")?.to_string();
+        let start_group_name = ctx.text(ctx.select_first(ctx.select_first(row_elements[1], &a_selector)?, &hidden_xs_selector)?, "start group")?.to_string();
+        let event_a = ctx.select_first(row_elements[2], &a_selector)?;
+        let event_short = ctx.text(ctx.select_first(event_a, &visible_xs_inline_selector)?, "event short name")?.to_string();
+        let event_long = ctx.text(ctx.select_first(event_a, &hidden_xs_selector)?, "event name")?.to_string();
 
-        timetable.push(TimetableEvent {
+        Ok(TimetableEvent {
             time,
             startlist_url,
             start_group_name,
             event_short,
             event_name: event_long
         })
+    };
+
+    let mut timetable = Vec::new();
+    for (row_idx, row) in html.select(&timetable_selector).enumerate() {
+        let row_elements: Vec<_> = row.select(&row_element_selector).collect();
+        if row_elements.len() == 1 {
+            // skip it, it's a multi-day event and row contains only the day.
+            continue;
+        }
+
+        match parse_timetable_row(ctx.row(row_idx), row_elements) {
+            Ok(v) => timetable.push(v),
+            Err(e) => warnings.skip_row(e, &row.html())?,
+        }
+    }
+
+
+    // first the rounds that are in the start group of the timetable, e.g. `Women - Final`
+    let mut claimed = vec![false; timetable.len()];
+    let mut unmatched = Vec::new();
+    for (idx, result) in res.iter_mut().enumerate() {
+        let round = result.round.as_ref().map(|v| v.name.to_lowercase()).filter(|v| !v.is_empty());
+        let found = (0..timetable.len()).find(|t| {
+            !claimed[*t] && timetable[*t].is_event_of(result)
+                && round.as_ref().is_some_and(|round| timetable[*t].start_group_name.to_lowercase().contains(round))
+        });
+        match found {
+            Some(t) => {
+                claimed[t] = true;
+                result.timetable_event = Some(timetable[t].clone());
+            }
+            None => unmatched.push(idx),
+        }
     }
 
+    // otherwise the nth result of an event is the nth time it's left in the timetable
+    for idx in unmatched {
+        if let Some(t) = (0..timetable.len()).find(|t| !claimed[*t] && timetable[*t].is_event_of(&res[idx])) {
+            claimed[t] = true;
+            res[idx].timetable_event = Some(timetable[t].clone());
+        }
+    }
 
     Ok(AthleteEventResults { name, competition_id, results: res, timetable, participated_in })
 }
@@ -1,6 +1,9 @@
-use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html};
 use serde::{Deserialize, Serialize};
+use crate::error::ParseDetail;
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+
+const MODEL: &'static str = "athlete_list";
 
 // Captures the age (just the digits) in the first capture group, and the club name in the second capture group
 const REGEX_AGE_AND_CLUB: &'static str = r#"([\d]{1,3}) years \| ([\s\S]{1,})"#;
@@ -17,45 +20,60 @@ pub struct AthleteListElement {
     pub age: u8,
 }
 
-pub fn parse(html: Html) -> anyhow::Result<AthleteList> {
-    let selector =
-        Selector::parse("div.list-athletes > ul > li > a > div.item-inner > div.item-title")
-            .unwrap();
-    let re_age_and_club = Regex::new(REGEX_AGE_AND_CLUB).unwrap();
-    let re_athlete_id = Regex::new(REGEX_ATHLETE_ID).unwrap();
+pub fn parse(html: Html) -> crate::Result<AthleteList> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
 
-    let mut res = Vec::new();
-    for i in html.select(&selector) {
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<AthleteList>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<AthleteList> {
+    let selector = Sel::new("div.list-athletes > ul > li > a > div.item-inner > div.item-title");
+    let re_age_and_club = Re::new(REGEX_AGE_AND_CLUB);
+    let re_athlete_id = Re::new(REGEX_ATHLETE_ID);
+    let ctx = ParseCtx::new(MODEL);
+
+    let parse_row = |ctx: ParseCtx, i: ElementRef| -> crate::Result<AthleteListElement> {
         let texts: Vec<&str> = i
             .text()
             .map(|v| v.trim())
             .filter(|v| !v.is_empty())
             .collect();
 
-        let name = texts[0].replace("  ", " ");
-        let age_and_club = texts[1];
+        let (name, age_and_club) = match texts.as_slice() {
+            [name, age_and_club, ..] => (name.replace("  ", " "), *age_and_club),
+            _ => return Err(ctx.error(ParseDetail::MissingText { field: "age and club" })),
+        };
 
-        let captures = re_age_and_club.captures_iter(&age_and_club).next().unwrap();
+        let captures = ctx.captures(&re_age_and_club, age_and_club)?;
 
+        // the onclick is on the <a> two levels up
         let onclick = i
             .parent()
-            .unwrap()
-            .parent()
-            .unwrap()
-            .value()
-            .as_element()
-            .unwrap()
-            .attr("onclick")
-            .unwrap();
+            .and_then(|v| v.parent())
+            .and_then(|v| v.value().as_element())
+            .and_then(|v| v.attr("onclick"))
+            .ok_or_else(|| ctx.error(ParseDetail::MissingAttribute { attribute: "onclick" }))?;
 
-        let athlete_id: u32 = re_athlete_id.captures_iter(onclick).next().unwrap()[1].parse()?;
+        let athlete_id: u32 = ctx.value("athlete id", &ctx.captures(&re_athlete_id, onclick)?[1])?;
 
-        res.push(AthleteListElement {
+        Ok(AthleteListElement {
             id: athlete_id,
             name: name.to_string(),
             club_name: captures[2].to_string(),
-            age: captures[1].parse()?,
-        });
+            age: ctx.value("age", &captures[1])?,
+        })
+    };
+
+    let mut res = Vec::new();
+    for (idx, i) in html.select(&selector).enumerate() {
+        match parse_row(ctx.row(idx), i) {
+            Ok(v) => res.push(v),
+            Err(e) => warnings.skip_row(e, &i.html())?,
+        }
     }
 
     Ok(res)
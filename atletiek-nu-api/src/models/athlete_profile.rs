@@ -1,14 +1,17 @@
 use std::collections::HashMap;
 use chrono::NaiveDate;
 use log::{error, trace, warn};
-use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html};
 use serde::{Deserialize, Serialize};
+use crate::error::ParseDetail;
 use crate::models::competition_registrations_list::{self, CompetitionRegistrationList};
-use crate::util::round_float_to_digits;
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+use crate::performance::{Performance, PerformanceKind};
+use crate::event::Event;
+
+const MODEL: &'static str = "athlete_profile";
 
 const REGEX_PB_SORT_DATA: &'static str = r#"([0-9]{4})([0-9]{2})([0-9]{2})([\w\s-]{0,}) \(([\w]{0,})\)"#;
-const REGEX_PERFORMANCE: &'static str = r#"([0-9]{0,}):([0-9]{0,})[\.,]([0-9]{0,})([h]{0,})"#;
 const REGEX_ATTRIBUTE: &'static str = r#"([\d.]{0,})(cm|kg|gr)"#;
 const REGEX_GRAPH_INFO: &'static str = r#"title: \{text: '([\w\d\- ]+)'\},subtitle: \{text: '(\d+) results'\}"#;
 const REGEX_GRAPH_POINTS: &'static str = r#"\[Date.UTC\((\d{0,}), (\d{0,}), (\d{0,})\),([\d.]{0,})\]"#;
@@ -27,7 +30,7 @@ pub struct AthleteProfile {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersonalBestItem {
     pub event: String,
-    pub performance: f32,
+    pub performance: Performance,
     pub wind_speed: Option<f32>,
     pub display_performance: String,
     pub hand_measured: bool,
@@ -39,6 +42,13 @@ pub struct PersonalBestItem {
     pub attribute: Option<EventAttribute>
 }
 
+impl PersonalBestItem {
+    /// From the long name, e.g. `60 meters hurdles`
+    pub fn canonical_event(&self) -> Event {
+        Event::parse(&self.event)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum EventAttribute {
     Height(f32),
@@ -52,198 +62,223 @@ pub struct EventGraph {
     pub specification: EventAttribute,
     pub event: String,
     pub event_id: i32,
-    pub points: Vec<(NaiveDate, f32)>
+    pub points: Vec<(NaiveDate, Performance)>
 }
 
-pub fn parse(html: Html) -> anyhow::Result<AthleteProfile> {
-    let pb_table_row_selector = Selector::parse("div#records > table#persoonlijkerecords > tbody > tr").unwrap();
-    let td_selector = Selector::parse("td").unwrap();
-    let sort_span_selector = Selector::parse("span.sortData").unwrap();
-    let subtext_span_selector = Selector::parse("span.subtext").unwrap();
-    let span_selector = Selector::parse("span").unwrap();
-    let graph_selector = Selector::parse("div#graphTabs > div").unwrap();
-    let script_selector = Selector::parse("script").unwrap();
-    let spec_div_selector = Selector::parse("div > a.specification-selector").unwrap();
-    let page_title_selector = Selector::parse("div.pageTitle").unwrap();
-    let re_pb_data = Regex::new(REGEX_PB_SORT_DATA).unwrap();
-    let re_performance = Regex::new(REGEX_PERFORMANCE).unwrap();
-    let re_graph_info = Regex::new(REGEX_GRAPH_INFO).unwrap();
-    let re_graph_points = Regex::new(REGEX_GRAPH_POINTS).unwrap();
-    let re_graph_event_id = Regex::new(REGEX_GRAPH_EVENT_ID).unwrap();
-    let re_graph_spec = Regex::new(REGEX_GRAPH_SPECIFICATION).unwrap();
-    let re_div_spec = Regex::new(REGEX_DIV_SPECIFICATION).unwrap();
-
-    let mut name = String::new();
-    let mut page_title_texts = html.select(&page_title_selector).next().unwrap().text();
-    while name.is_empty() {
-        name = page_title_texts.next().unwrap().trim().replace("  ", " ");
+impl EventGraph {
+    /// From the long name, e.g. `Shot put`
+    pub fn canonical_event(&self) -> Event {
+        Event::parse(&self.event)
     }
+}
 
-    let competitions = competition_registrations_list::parse(html.root_element())?;
-    let mut personal_bests = Vec::new();
+pub fn parse(html: Html) -> crate::Result<AthleteProfile> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
 
-    for row in html.select(&pb_table_row_selector) {
-        let mut cells = row.select(&td_selector);
-        let mut item = PersonalBestItem {
-            event: String::new(),
-            performance: 0.0,
-            wind_speed: None,
-            display_performance: String::new(),
-            hand_measured: false,
-            location: String::new(),
-            country: String::new(),
-            date: NaiveDate::from_ymd_opt(0, 1, 1).unwrap(),
-            not_important: false,
-            attribute: None,
-        };
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<AthleteProfile>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
 
-        item.not_important = row.value().attr("class").unwrap().contains("notThatImportant");
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<AthleteProfile> {
+    let pb_table_row_selector = Sel::new("div#records > table#persoonlijkerecords > tbody > tr");
+    let td_selector = Sel::new("td");
+    let sort_span_selector = Sel::new("span.sortData");
+    let subtext_span_selector = Sel::new("span.subtext");
+    let span_selector = Sel::new("span");
+    let graph_selector = Sel::new("div#graphTabs > div");
+    let script_selector = Sel::new("script");
+    let spec_div_selector = Sel::new("div > a.specification-selector");
+    let page_title_selector = Sel::new("div.pageTitle");
+    let re_pb_data = Re::new(REGEX_PB_SORT_DATA);
+    let re_graph_info = Re::new(REGEX_GRAPH_INFO);
+    let re_graph_points = Re::new(REGEX_GRAPH_POINTS);
+    let re_graph_event_id = Re::new(REGEX_GRAPH_EVENT_ID);
+    let re_graph_spec = Re::new(REGEX_GRAPH_SPECIFICATION);
+    let re_div_spec = Re::new(REGEX_DIV_SPECIFICATION);
+    let ctx = ParseCtx::new(MODEL);
+
+    let page_title = ctx.select_first(html.root_element(), &page_title_selector)?;
+    let name = ctx.text(page_title, "name")?.replace("  ", " ");
+
+    let competitions = competition_registrations_list::parse_with(html.root_element(), warnings)?;
+
+    let parse_pb_row = |ctx: ParseCtx, row: ElementRef, warnings: &mut Warnings| -> crate::Result<PersonalBestItem> {
+        let mut cells = row.select(&td_selector);
+        let mut next_cell = || cells.next().ok_or_else(|| ctx.error(ParseDetail::MissingElement { selector: td_selector.css() }));
+        let not_important = row.value().attr("class").unwrap_or_default().contains("notThatImportant");
+        let mut hand_measured = false;
+        let mut attribute = None;
 
-        item.event = {
-            let element = cells.next().unwrap();
+        let event = {
+            let element = next_cell()?;
 
             for i in element.select(&subtext_span_selector) {
-                let text = i.text().next().unwrap().trim().to_string();
+                let text = i.text().next().unwrap_or_default().trim().to_string();
                 if text.is_empty() {
                     continue;
                 }
                 trace!("Got attribute {}", text);
                 match text.to_lowercase().as_str() {
                     "manual" => {
-                        item.hand_measured = true
+                        hand_measured = true
                     },
                     "unknown height" => {
-                        item.attribute = Some(EventAttribute::UnknownHeight)
+                        attribute = Some(EventAttribute::UnknownHeight)
                     },
                     x => {
                         if !(x.contains("cm") || x.contains("gr") || x.contains("kg")) {
-                            warn!("Unexpected attribute: {}", x);
+                            warnings.warn(&ctx, "attribute", format!("Unexpected attribute: {}", x), &i.html());
                             continue;
                         }
-                        item.attribute = parse_attribute(x);
+                        attribute = parse_attribute(x);
                     }
                 }
             }
 
-            element.text().next().unwrap().trim().to_string()
+            ctx.text(element, "event")?.to_string()
         };
 
-        {
-            let element = cells.next().unwrap();
-            let performance_text = element.text().next().unwrap().trim();
-            item.display_performance = performance_text.to_string();
-
-            // prepend 0: for a little bit of regex hacking so the first group always captures
-            // even if there is no : in the text (e.g. when it's a distance)
-            let mut padded_performance_text = String::from("0:");
-            padded_performance_text.push_str(performance_text);
-            padded_performance_text.push_str(",0");
-            trace!("Got performance text {} -> {}", performance_text, padded_performance_text);
-            let captures = re_performance.captures_iter(&padded_performance_text).next().unwrap();
-
-            let minutes = captures[1].parse::<u32>().unwrap();
-            let seconds = captures[2].parse::<u32>().unwrap();
-            let milliseconds = captures[3].parse::<u32>().unwrap();
-            let ms_accuracy = captures[3].len();
+        let (performance, display_performance, wind_speed) = {
+            let element = next_cell()?;
+            let performance_text = ctx.text(element, "performance")?;
 
             // contains 'h' if hand measured
-            item.hand_measured = !captures[4].is_empty();
-
-            item.performance = round_float_to_digits(minutes as f32 * 60.0 + seconds as f32 + milliseconds as f32 / f32::powi(10.0, ms_accuracy as _), 3);
-            trace!("Parsed to {:.2} hand measured {}", item.performance, item.hand_measured);
+            hand_measured |= performance_text.split_whitespace().next().is_some_and(|v| v.ends_with('h'));
+            let performance = Performance::parse(PerformanceKind::from_event(&event), performance_text)
+                .ok_or_else(|| ctx.invalid("performance", performance_text, "not a time, distance or points"))?;
+            trace!("Parsed to {} hand measured {}", performance, hand_measured);
 
+            let mut wind_speed = None;
             if let Some(Some(span)) = element.select(&subtext_span_selector).next().map(|v| v.select(&span_selector).next()) {
                 // wind speed
-                let text = span.text().next().unwrap();
-                item.wind_speed = crate::components::wind_speed::parse(text);
-                trace!("Got wind speed text {} -> {:?}", text, item.wind_speed);
+                let text = span.text().next().unwrap_or_default();
+                wind_speed = crate::components::wind_speed::parse(text);
+                trace!("Got wind speed text {} -> {:?}", text, wind_speed);
             }
-        }
 
-        {
-            let element = cells.next().unwrap();
-            let span = element.select(&sort_span_selector).next().unwrap();
-            let data_text = span.value().attr("data").unwrap().to_string();
-
-            trace!("Got pb sort text {}", data_text);
-
-            let captures = re_pb_data.captures_iter(&data_text).next().unwrap();
-            item.date = NaiveDate::from_ymd_opt(
-                captures[1].parse().unwrap(),
-                captures[2].parse().unwrap(),
-                captures[3].parse().unwrap()
-            ).unwrap();
-            item.location = captures[4].to_string();
-            item.country = captures[5].to_string();
-        }
+            (performance, performance_text.to_string(), wind_speed)
+        };
 
-        personal_bests.push(item);
+        let element = next_cell()?;
+        let span = ctx.select_first(element, &sort_span_selector)?;
+        let data_text = ctx.attr(span, "data")?.to_string();
+
+        trace!("Got pb sort text {}", data_text);
+
+        let captures = ctx.captures(&re_pb_data, &data_text)?;
+        let date = NaiveDate::from_ymd_opt(
+            ctx.value("year", &captures[1])?,
+            ctx.value("month", &captures[2])?,
+            ctx.value("day", &captures[3])?
+        ).ok_or_else(|| ctx.invalid("date", &data_text, "out of range"))?;
+
+        Ok(PersonalBestItem {
+            event,
+            performance,
+            wind_speed,
+            display_performance,
+            hand_measured,
+            location: captures[4].to_string(),
+            country: captures[5].to_string(),
+            date,
+            not_important,
+            attribute,
+        })
+    };
+
+    let mut personal_bests = Vec::new();
+    for (idx, row) in html.select(&pb_table_row_selector).enumerate() {
+        match parse_pb_row(ctx.row(idx), row, warnings) {
+            Ok(v) => personal_bests.push(v),
+            Err(e) => warnings.skip_row(e, &row.html())?,
+        }
     }
 
-    let mut graphs = Vec::new();
+    let parse_spec = |div: ElementRef| -> crate::Result<(String, EventAttribute)> {
+        let target = ctx.attr(div, "data-target")?;
+        let (event_id, spec) = {
+            let captures = ctx.captures(&re_div_spec, target)?;
+            (captures[1].to_string(), captures[2].to_string())
+        };
+        let text = div.text().next().unwrap_or_default();
+        let attr = match text {
+            "Everything" => EventAttribute::All,
+            _ => parse_attribute(text)
+                .ok_or_else(|| ctx.invalid("specification", text, "not a height or weight"))?,
+        };
+        trace!("Found spec {} for event {} -> {:?}", spec, event_id, attr);
+        Ok((spec, attr))
+    };
 
     let mut specs: HashMap<String, EventAttribute> = HashMap::new();
 
     for graph in html.select(&graph_selector) {
         for div in graph.select(&spec_div_selector) {
-            let target = div.value().attr("data-target").unwrap();
-            let (event_id, spec) = {
-                let captures = re_div_spec.captures_iter(target).next().unwrap();
-                (captures[1].to_string(), captures[2].to_string())
-            };
-            let text = div.text().next().unwrap();
-            let attr = match text {
-                "Everything" => EventAttribute::All,
-                _ => parse_attribute(text).unwrap(),
-            };
-            trace!("Found spec {} for event {} -> {:?}", spec, event_id, attr);
-            if specs.insert(spec.clone(), attr).is_some() {
-                warn!("spec id collision on {}", spec);
+            match parse_spec(div) {
+                Ok((spec, attr)) => if specs.insert(spec.clone(), attr).is_some() {
+                    warnings.warn(&ctx, "specification", format!("spec id collision on {}", spec), &div.html());
+                },
+                Err(e) => warnings.skip_row(e, &div.html())?,
             }
         }
     }
     // we want to get all the specs first
 
+    let parse_graph = |script: ElementRef| -> crate::Result<EventGraph> {
+        let html = script.inner_html();
+
+        let text = html.trim().replace("\t", "").replace("\n", "");
+
+        let (event_name, point_count) = {
+            let captures = ctx.captures(&re_graph_info, &text)?;
+            (captures[1].to_string(), ctx.value::<usize>("point count", &captures[2])?)
+        };
+        let kind = PerformanceKind::from_event(&event_name);
+        let event_id = ctx.captures(&re_graph_event_id, &text)?[1].to_string();
+        let specification = ctx.captures(&re_graph_spec, &text)?[1].to_string();
+        trace!("Found graph for {}, {} points (event id {}, spec {})", event_name, point_count, event_id, specification);
+
+        let mut points = Vec::new();
+
+        for point in re_graph_points.captures_iter(&text) {
+            trace!("Point capture {} {} {} {}", point[1].to_string(), point[2].to_string(), point[3].to_string(), point[4].to_string());
+            let date = NaiveDate::from_ymd_opt(
+                ctx.value("year", &point[1])?,
+                ctx.value::<u32>("month", &point[2])? + 1, // in javascript months are 0-based
+                ctx.value("day", &point[3])?
+            ).ok_or_else(|| ctx.invalid("graph date", &point[0], "out of range"))?;
+            let performance = Performance::from_value(kind, ctx.value("graph performance", &point[4])?)
+                .ok_or_else(|| ctx.invalid("graph performance", &point[4], "negative"))?;
+
+            points.push((date, performance));
+        }
+
+        Ok(EventGraph {
+            specification: {
+                if specification == event_id {
+                    EventAttribute::All
+                } else {
+                    specs.get(&specification)
+                        .ok_or_else(|| ctx.invalid("graph specification", &specification, "no matching specification selector"))?
+                        .to_owned()
+                }
+            },
+            event_id: ctx.value("graph event id", &event_id)?,
+            event: event_name,
+            points
+        })
+    };
+
+    let mut graphs = Vec::new();
     for graph in html.select(&graph_selector) {
         for script in graph.select(&script_selector) {
-            let html = script.inner_html();
-
-            let text = html.trim().replace("\t", "").replace("\n", "");
-
-            let (event_name, point_count) = {
-                let captures = re_graph_info.captures_iter(&text).next().unwrap();
-                (captures[1].to_string(), captures[2].parse::<usize>().unwrap())
-            };
-            let event_id = re_graph_event_id.captures_iter(&text).next().unwrap()[1].to_string();
-            let specification = re_graph_spec.captures_iter(&text).next().unwrap()[1].to_string();
-            trace!("Found graph for {}, {} points (event id {}, spec {})", event_name, point_count, event_id, specification);
-
-            let mut points = Vec::new();
-
-            for point in re_graph_points.captures_iter(&text) {
-                trace!("Point capture {} {} {} {}", point[1].to_string(), point[2].to_string(), point[3].to_string(), point[4].to_string());
-                let date = NaiveDate::from_ymd_opt(
-                    point[1].parse().unwrap(),
-                    point[2].parse::<u32>().unwrap() + 1, // in javascript months are 0-based
-                    point[3].parse().unwrap()
-                ).unwrap();
-                let performance = point[4].parse().unwrap();
-
-                points.push((date, performance));
+            match parse_graph(script) {
+                Ok(v) => graphs.push(v),
+                Err(e) => warnings.skip_row(e, &script.html())?,
             }
-
-            graphs.push(EventGraph {
-                specification: {
-                    if specification == event_id {
-                        EventAttribute::All
-                    } else {
-                        specs.get(&specification).unwrap().to_owned()
-                    }
-                },
-                event_id: event_id.parse().unwrap(),
-                event: event_name,
-                points
-            })
         }
     }
 
@@ -253,10 +288,16 @@ pub fn parse(html: Html) -> anyhow::Result<AthleteProfile> {
 }
 
 fn parse_attribute(text: &str) -> Option<EventAttribute> {
-    let re_attribute = Regex::new(REGEX_ATTRIBUTE).unwrap();
-
-    let captures = re_attribute.captures_iter(&text).next().unwrap();
-    let value: f32 = captures[1].parse().unwrap();
+    let re_attribute = Re::new(REGEX_ATTRIBUTE);
+
+    let captures = re_attribute.captures(text)?;
+    let value: f32 = match captures[1].parse() {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("Failed to parse attribute value {}: {}", &captures[1], e);
+            return None;
+        }
+    };
     match captures[2].to_string().as_str() {
         "cm" => Some(EventAttribute::Height(value / 100.0)),
         "gr" => Some(EventAttribute::Weight(value / 1000.0)),
@@ -0,0 +1,208 @@
+use chrono::NaiveDate;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use crate::components::table::{cell_text, Headers};
+use crate::error::ParseDetail;
+use crate::models::athlete_event_result::AthleteEventResults;
+use crate::models::registrations_list_web::RegistrationsWebListElement;
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+
+const MODEL: &str = "club";
+
+const REGEX_CLUB_ID: &str = r#"vereniging/main/([0-9]{1,})/"#;
+const REGEX_ATHLETE_ID: &str = r#"atleet/profiel/([0-9]{1,})/"#;
+const REGEX_COMPETITION_ID: &str = r#"wedstrijd/main/([0-9]{1,})/"#;
+
+/// The main page of a club, `vereniging/main/{id}/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Club {
+    pub club_id: u32,
+    pub name: String,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub website: Option<String>,
+    pub members: Vec<ClubMember>,
+    /// The competitions the club organises or its members attend, newest first
+    pub competitions: Vec<ClubCompetition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClubMember {
+    /// See [`crate::AtnClient::get_athlete_profile`]
+    pub athlete_id: u32,
+    pub name: String,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClubCompetition {
+    pub competition_id: u32,
+    pub name: String,
+    pub date: NaiveDate,
+    pub location: Option<String>,
+    /// Organised by the club
+    pub organiser: bool,
+}
+
+/// Everything the athletes of a club did at one competition, see [`crate::AtnClient::get_club_competition_results`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClubCompetitionResults {
+    pub club_id: u32,
+    pub competition_id: u32,
+    pub athletes: Vec<ClubAthleteResults>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClubAthleteResults {
+    pub registration: RegistrationsWebListElement,
+    /// `None` if the athlete has no results (yet)
+    pub results: Option<AthleteEventResults>,
+}
+
+/// Index of every column in the members table, if the page has it
+struct MemberColumns {
+    name: Option<usize>,
+    category: Option<usize>,
+}
+
+impl MemberColumns {
+    fn new(headers: &Headers) -> Self {
+        Self {
+            name: headers.find(&["Name"]),
+            category: headers.find(&["Cat.", "Category"]),
+        }
+    }
+}
+
+/// Index of every column in the competitions table, if the page has it
+struct CompetitionColumns {
+    date: Option<usize>,
+    competition: Option<usize>,
+    venue: Option<usize>,
+}
+
+impl CompetitionColumns {
+    fn new(headers: &Headers) -> Self {
+        Self {
+            date: headers.find(&["Date"]),
+            competition: headers.find(&["Competition"]),
+            venue: headers.find(&["Venue"]),
+        }
+    }
+}
+
+/// Expects the DESKTOP site
+pub fn parse(html: Html) -> crate::Result<Club> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
+
+/// Expects the DESKTOP site
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<Club>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<Club> {
+    let name_selector = Sel::new("div.pageTitle");
+    let canonical_selector = Sel::new("link[rel='canonical']");
+    let info_row_selector = Sel::new("table#verenigingsinfo > tbody > tr");
+    let members_selector = Sel::new("table#leden");
+    let competitions_selector = Sel::new("table#wedstrijden");
+    let header_selector = Sel::new("thead > tr > th");
+    let row_selector = Sel::new("tbody > tr");
+    let th_selector = Sel::new("th");
+    let td_selector = Sel::new("td");
+    let a_selector = Sel::new("a");
+    let data_span_selector = Sel::new("span.sortData");
+    let organiser_selector = Sel::new("span.organisator");
+    let re_club_id = Re::new(REGEX_CLUB_ID);
+    let re_athlete_id = Re::new(REGEX_ATHLETE_ID);
+    let re_competition_id = Re::new(REGEX_COMPETITION_ID);
+    let ctx = ParseCtx::new(MODEL);
+
+    let name = ctx.text(ctx.select_first(html.root_element(), &name_selector)?, "name")?.to_string();
+    let club_url = ctx.attr(ctx.select_first(html.root_element(), &canonical_selector)?, "href")?;
+    let club_id = ctx.value("club id", &ctx.captures(&re_club_id, club_url)?[1])?;
+
+    let mut club = Club {
+        club_id,
+        name,
+        city: None,
+        country: None,
+        website: None,
+        members: Vec::new(),
+        competitions: Vec::new(),
+    };
+
+    for (idx, row) in html.select(&info_row_selector).enumerate() {
+        let ctx = ctx.row(idx);
+        let info = ctx.select_first(row, &th_selector).and_then(|th| Ok((ctx.text(th, "label")?, ctx.select_first(row, &td_selector)?)));
+        let (label, td) = match info {
+            Ok(v) => v,
+            Err(e) => {
+                warnings.skip_row(e, &row.html())?;
+                continue;
+            }
+        };
+
+        match label {
+            "City" => club.city = cell_text(td),
+            "Country" => club.country = cell_text(td),
+            "Website" => club.website = td.select(&a_selector).next().and_then(|v| v.value().attr("href")).map(|v| v.to_string()),
+            _ => (),
+        }
+    }
+
+    let parse_member = |ctx: ParseCtx, columns: &MemberColumns, row: ElementRef| -> crate::Result<ClubMember> {
+        let cells: Vec<ElementRef> = row.select(&td_selector).collect();
+        let cell = |column: Option<usize>| column.and_then(|v| cells.get(v)).copied();
+
+        let a = ctx.select_first(cell(columns.name).ok_or_else(|| ctx.error(ParseDetail::MissingElement { selector: td_selector.css() }))?, &a_selector)?;
+        Ok(ClubMember {
+            athlete_id: ctx.value("athlete id", &ctx.captures(&re_athlete_id, ctx.attr(a, "href")?)?[1])?,
+            name: ctx.text(a, "name")?.replace("  ", " "),
+            category: cell(columns.category).and_then(cell_text),
+        })
+    };
+
+    let parse_competition = |ctx: ParseCtx, columns: &CompetitionColumns, row: ElementRef| -> crate::Result<ClubCompetition> {
+        let cells: Vec<ElementRef> = row.select(&td_selector).collect();
+        let cell = |column: Option<usize>| column.and_then(|v| cells.get(v)).copied();
+        let missing_td = || ctx.error(ParseDetail::MissingElement { selector: td_selector.css() });
+
+        let date_text = ctx.attr(ctx.select_first(cell(columns.date).ok_or_else(missing_td)?, &data_span_selector)?, "data")?;
+        let competition_cell = cell(columns.competition).ok_or_else(missing_td)?;
+        let a = ctx.select_first(competition_cell, &a_selector)?;
+
+        Ok(ClubCompetition {
+            competition_id: ctx.value("competition id", &ctx.captures(&re_competition_id, ctx.attr(a, "href")?)?[1])?,
+            name: ctx.text(a, "competition")?.to_string(),
+            date: NaiveDate::parse_from_str(date_text, "%Y%m%d").map_err(|e| ctx.invalid("date", date_text, e))?,
+            location: cell(columns.venue).and_then(cell_text),
+            organiser: competition_cell.select(&organiser_selector).next().is_some(),
+        })
+    };
+
+    if let Some(table) = html.select(&members_selector).next() {
+        let columns = MemberColumns::new(&Headers::new(table, &header_selector));
+        for (row_idx, row) in table.select(&row_selector).enumerate() {
+            match parse_member(ctx.row(row_idx), &columns, row) {
+                Ok(v) => club.members.push(v),
+                Err(e) => warnings.skip_row(e, &row.html())?,
+            }
+        }
+    }
+
+    if let Some(table) = html.select(&competitions_selector).next() {
+        let columns = CompetitionColumns::new(&Headers::new(table, &header_selector));
+        for (row_idx, row) in table.select(&row_selector).enumerate() {
+            match parse_competition(ctx.row(row_idx), &columns, row) {
+                Ok(v) => club.competitions.push(v),
+                Err(e) => warnings.skip_row(e, &row.html())?,
+            }
+        }
+    }
+
+    Ok(club)
+}
@@ -0,0 +1,295 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use crate::error::ParseDetail;
+use crate::models::competitions_list_web::CompetitionKind;
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+
+const MODEL: &str = "competition_details";
+
+const REGEX_COMPETITION_ID: &str = r#"wedstrijd/main/([0-9]{1,})/"#;
+const REGEX_CLUB_ID: &str = r#"vereniging/main/([0-9]{1,})/"#;
+// 1: day of month, 2: month (MAR, AUG, etc.), 3: year, 4 and 5: optional hour and minute
+const REGEX_DATE: &str = r#"\w{3} (\d{2}) (\w{3}) (\d{4})(?: (\d{2}):(\d{2}))?"#;
+// 1: euros, 2: cents, 3: what the fee is for
+const REGEX_FEE: &str = r#"€\s*(\d{1,}),(\d{2})\s*(.*)"#;
+
+/// The main page of a competition, `wedstrijd/main/{id}/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompetitionDetails {
+    pub competition_id: u32,
+    pub name: String,
+    pub organiser: Option<String>,
+    pub organiser_club_id: Option<u32>,
+    pub venue: Option<Venue>,
+    pub start_date: NaiveDate,
+    /// Same as the start date for a single day competition
+    pub end_date: NaiveDate,
+    pub kind: Option<CompetitionKind>,
+    /// Local time of the competition
+    pub registration_deadline: Option<NaiveDateTime>,
+    pub fees: Vec<Fee>,
+    pub contact: Option<Contact>,
+    pub website: Option<String>,
+    pub documents: Vec<Document>,
+    /// The events that can be entered, per category
+    pub events: Vec<CategoryEvents>,
+    pub registrations_open: bool,
+    pub results_available: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Venue {
+    pub name: String,
+    /// Street and city, one line each
+    pub address: Vec<String>,
+    pub country: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Fee {
+    pub amount_cents: u32,
+    /// e.g. `per event`
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Contact {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Document {
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CategoryEvents {
+    pub category: String,
+    pub events: Vec<String>,
+}
+
+/// Parsed from one row of the info table
+enum Info {
+    Dates(NaiveDate, NaiveDate),
+    Organiser(String, Option<u32>),
+    Venue(Venue),
+    Kind(Option<CompetitionKind>),
+    RegistrationDeadline(NaiveDateTime),
+    Fees(Vec<Fee>),
+    Contact(Contact),
+    Website(String),
+    Unknown,
+}
+
+/// Expects the DESKTOP site
+pub fn parse(html: Html) -> crate::Result<CompetitionDetails> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
+
+/// Expects the DESKTOP site
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<CompetitionDetails>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<CompetitionDetails> {
+    let name_selector = Sel::new("div.pageTitle");
+    let competition_selector = Sel::new("div#menubottom > a.hidden-xs");
+    let info_row_selector = Sel::new("table#wedstrijdinfo > tbody > tr");
+    let th_selector = Sel::new("th");
+    let td_selector = Sel::new("td");
+    let a_selector = Sel::new("a");
+    let mailto_selector = Sel::new("a[href^='mailto:']");
+    let flag_selector = Sel::new("img[title]");
+    let document_selector = Sel::new("ul.bijlagen > li > a");
+    let event_row_selector = Sel::new("table.onderdelentabel > tbody > tr");
+    let event_selector = Sel::new("span.onderdeel");
+    let register_selector = Sel::new("a[href*='/wedstrijd/inschrijven/']");
+    let results_selector = Sel::new("a[href*='/wedstrijd/uitslagen/']");
+    let re_competition_id = Re::new(REGEX_COMPETITION_ID);
+    let re_club_id = Re::new(REGEX_CLUB_ID);
+    let re_date = Re::new(REGEX_DATE);
+    let re_fee = Re::new(REGEX_FEE);
+    let ctx = ParseCtx::new(MODEL);
+
+    let name = ctx.text(ctx.select_first(html.root_element(), &name_selector)?, "name")?.to_string();
+    let competition_url = ctx.attr(ctx.select_first(html.root_element(), &competition_selector)?, "href")?;
+    let competition_id = ctx.value("competition id", &ctx.captures(&re_competition_id, competition_url)?[1])?;
+
+    let parse_date = |ctx: &ParseCtx, text: &str| -> crate::Result<NaiveDateTime> {
+        let captures = ctx.captures(&re_date, text)?;
+        let day = ctx.value("day", &captures[1])?;
+        let month = crate::components::date::month(&captures[2])
+            .ok_or_else(|| ctx.invalid("month", &captures[2], "unknown month"))?;
+        let year = ctx.value("year", &captures[3])?;
+        let date = NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| ctx.invalid("date", text, "out of range"))?;
+
+        let time = match (captures.get(4), captures.get(5)) {
+            (Some(hour), Some(minute)) => {
+                NaiveTime::from_hms_opt(ctx.value("hour", hour.as_str())?, ctx.value("minute", minute.as_str())?, 0)
+                    .ok_or_else(|| ctx.invalid("time", text, "out of range"))?
+            }
+            _ => NaiveTime::MIN,
+        };
+
+        Ok(NaiveDateTime::new(date, time))
+    };
+
+    let parse_info_row = |ctx: ParseCtx, row: ElementRef, warnings: &mut Warnings| -> crate::Result<Info> {
+        let label = ctx.text(ctx.select_first(row, &th_selector)?, "label")?;
+        let td = ctx.select_first(row, &td_selector)?;
+        // every line of the cell, split on the <br>s
+        let lines: Vec<&str> = td.text().map(|v| v.trim()).filter(|v| !v.is_empty()).collect();
+
+        let info = match label {
+            "Date" => {
+                let text = lines.join(" ");
+                let mut dates = text.split(" - ");
+                let start = parse_date(&ctx, dates.next().unwrap_or_default())?.date();
+                let end = match dates.next() {
+                    Some(v) => parse_date(&ctx, v)?.date(),
+                    None => start,
+                };
+                Info::Dates(start, end)
+            }
+            "Organisation" => {
+                let name = ctx.text(td, "organisation")?.to_string();
+                let club_id = match td.select(&a_selector).next() {
+                    Some(a) => Some(ctx.value("club id", &ctx.captures(&re_club_id, ctx.attr(a, "href")?)?[1])?),
+                    None => None,
+                };
+                Info::Organiser(name, club_id)
+            }
+            "Venue" => {
+                let mut lines = lines.into_iter().map(|v| v.to_string());
+                // the title also holds the continent, after a <br>
+                let country = td
+                    .select(&flag_selector)
+                    .next()
+                    .and_then(|v| v.value().attr("title"))
+                    .and_then(|v| v.split('<').next())
+                    .map(|v| v.trim().to_string());
+
+                Info::Venue(Venue {
+                    name: lines.next().ok_or_else(|| ctx.error(ParseDetail::MissingText { field: "venue" }))?,
+                    address: lines.collect(),
+                    country,
+                })
+            }
+            "Type" => {
+                let kind = match ctx.text(td, "type")? {
+                    "Indoor" => Some(CompetitionKind::Indoor),
+                    "Outdoor" => Some(CompetitionKind::Outdoor),
+                    "Cross country" => Some(CompetitionKind::CrossCountry),
+                    "Road" => Some(CompetitionKind::Road),
+                    other => {
+                        warnings.warn(&ctx, "type", format!("Unknown competition type '{}'", other), &row.html());
+                        None
+                    }
+                };
+                Info::Kind(kind)
+            }
+            "Registration deadline" => Info::RegistrationDeadline(parse_date(&ctx, &lines.join(" "))?),
+            "Fees" => {
+                let mut fees = Vec::new();
+                for line in lines {
+                    let captures = ctx.captures(&re_fee, line)?;
+                    let euros: u32 = ctx.value("fee", &captures[1])?;
+                    let cents: u32 = ctx.value("fee", &captures[2])?;
+                    fees.push(Fee {
+                        amount_cents: euros.saturating_mul(100).saturating_add(cents),
+                        description: captures[3].trim().to_string(),
+                    });
+                }
+                Info::Fees(fees)
+            }
+            "Contact" => {
+                let email = td.select(&mailto_selector).next().map(|v| ctx.text(v, "email")).transpose()?;
+                let is_phone = |v: &&str| v.chars().any(|c| c.is_ascii_digit());
+                let rest: Vec<&str> = lines.into_iter().filter(|v| Some(*v) != email).collect();
+                let name = rest.iter().find(|v| !is_phone(v));
+                let phone = rest.iter().find(|v| is_phone(v));
+
+                Info::Contact(Contact {
+                    name: name.map(|v| v.to_string()),
+                    email: email.map(|v| v.to_string()),
+                    phone: phone.map(|v| v.to_string()),
+                })
+            }
+            "Website" => Info::Website(ctx.attr(ctx.select_first(td, &a_selector)?, "href")?.to_string()),
+            _ => Info::Unknown,
+        };
+
+        Ok(info)
+    };
+
+    let mut dates = None;
+    let mut details = CompetitionDetails {
+        competition_id,
+        name,
+        organiser: None,
+        organiser_club_id: None,
+        venue: None,
+        start_date: NaiveDate::MIN,
+        end_date: NaiveDate::MIN,
+        kind: None,
+        registration_deadline: None,
+        fees: Vec::new(),
+        contact: None,
+        website: None,
+        documents: Vec::new(),
+        events: Vec::new(),
+        registrations_open: html.select(&register_selector).next().is_some(),
+        results_available: html.select(&results_selector).next().is_some(),
+    };
+
+    for (idx, row) in html.select(&info_row_selector).enumerate() {
+        match parse_info_row(ctx.row(idx), row, warnings) {
+            Ok(Info::Dates(start, end)) => dates = Some((start, end)),
+            Ok(Info::Organiser(name, club_id)) => {
+                details.organiser = Some(name);
+                details.organiser_club_id = club_id;
+            }
+            Ok(Info::Venue(venue)) => details.venue = Some(venue),
+            Ok(Info::Kind(kind)) => details.kind = kind,
+            Ok(Info::RegistrationDeadline(deadline)) => details.registration_deadline = Some(deadline),
+            Ok(Info::Fees(fees)) => details.fees = fees,
+            Ok(Info::Contact(contact)) => details.contact = Some(contact),
+            Ok(Info::Website(website)) => details.website = Some(website),
+            Ok(Info::Unknown) => (),
+            Err(e) => warnings.skip_row(e, &row.html())?,
+        }
+    }
+
+    // without the dates the competition can't be placed at all
+    (details.start_date, details.end_date) = dates.ok_or_else(|| ctx.error(ParseDetail::MissingText { field: "date" }))?;
+
+    for (idx, i) in html.select(&document_selector).enumerate() {
+        let ctx = ctx.row(idx);
+        match ctx.text(i, "document").and_then(|name| Ok((name, ctx.attr(i, "href")?))) {
+            Ok((name, url)) => details.documents.push(Document {
+                name: name.to_string(),
+                url: url.to_string(),
+            }),
+            Err(e) => warnings.skip_row(e, &i.html())?,
+        }
+    }
+
+    for (idx, row) in html.select(&event_row_selector).enumerate() {
+        let ctx = ctx.row(idx);
+        match ctx.select_first(row, &td_selector).and_then(|v| ctx.text(v, "category")) {
+            Ok(category) => details.events.push(CategoryEvents {
+                category: category.to_string(),
+                events: row.select(&event_selector).filter_map(|v| ctx.text(v, "event").ok()).map(|v| v.to_string()).collect(),
+            }),
+            Err(e) => warnings.skip_row(e, &row.html())?,
+        }
+    }
+
+    Ok(details)
+}
@@ -1,7 +1,9 @@
 use chrono::NaiveDate;
-use regex::Regex;
-use scraper::{ElementRef, Selector};
+use scraper::ElementRef;
 use serde::{Deserialize, Serialize};
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+
+const MODEL: &'static str = "competition_registrations_list";
 
 const REGEX_PARTICIPANT_ID: &'static str = r#"https://www.athletics.app/atleet/main/([\d]{0,})/"#;
 const REGEX_LOCATION: &'static str = r#"([\w ]{0,})<br><span class="subtext">([\w ]{0,})</span>"#;
@@ -24,66 +26,79 @@ pub struct CompetitionLocation {
     pub flag_img_url: String
 }
 
-pub fn parse(element: ElementRef) -> anyhow::Result<CompetitionRegistrationList> {
-    let competition_list_selector = Selector::parse("div#wedstrijden > table#persoonlijkerecords").unwrap();
-    let competition_list_row_selector = Selector::parse("tbody > tr").unwrap();
-    let competition_list_link_selector = Selector::parse("td").unwrap();
-    let competition_list_sortdata_selector = Selector::parse("td > span.sortData").unwrap();
-    let competition_list_location_selector = Selector::parse("td > span.subtext > span.hidden-xs").unwrap();
-    let img_selector = Selector::parse("img").unwrap();
-    let a_selector = Selector::parse("a").unwrap();
-    let re_participant = Regex::new(REGEX_PARTICIPANT_ID).unwrap();
-    let re_location = Regex::new(REGEX_LOCATION).unwrap();
+pub fn parse(element: ElementRef) -> crate::Result<CompetitionRegistrationList> {
+    parse_with(element, &mut Warnings::new(ParseMode::Strict))
+}
+
+pub fn parse_lenient(element: ElementRef) -> crate::Result<Parsed<CompetitionRegistrationList>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(element, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(element: ElementRef, warnings: &mut Warnings) -> crate::Result<CompetitionRegistrationList> {
+    let competition_list_selector = Sel::new("div#wedstrijden > table#persoonlijkerecords");
+    let competition_list_row_selector = Sel::new("tbody > tr");
+    let competition_list_link_selector = Sel::new("td");
+    let competition_list_sortdata_selector = Sel::new("td > span.sortData");
+    let competition_list_location_selector = Sel::new("td > span.subtext > span.hidden-xs");
+    let img_selector = Sel::new("img");
+    let a_selector = Sel::new("a");
+    let re_participant = Re::new(REGEX_PARTICIPANT_ID);
+    let re_location = Re::new(REGEX_LOCATION);
+    let ctx = ParseCtx::new(MODEL);
+
+    let parse_row = |ctx: ParseCtx, row: ElementRef| -> crate::Result<CompetitionRegistration> {
+        let link = ctx.select_first(row, &competition_list_link_selector)?;
+        let date_text = ctx.attr(ctx.select_first(row, &competition_list_sortdata_selector)?, "data")?;
+        // we want to drop the location from the string so keep only digits
+        let date: String = date_text.chars().filter(|v| v.is_ascii_digit()).collect();
+
+        let date = NaiveDate::parse_from_str(&date, "%Y%m%d")
+            .map_err(|e| ctx.invalid("date", date_text, e))?;
+
+        let text = ctx.text(link, "competition name")?.to_string();
+        let participant_id = if let Some(v) = link.select(&a_selector).next() {
+            // if there is a child, it will be <a> with a link to the competition
+            let s = ctx.attr(v, "href")?;
+            ctx.value("participant id", &ctx.captures(&re_participant, s)?[1])?
+        } else { 0 };
+
+        let location_element = ctx.select_first(row, &competition_list_location_selector)?;
+        let place = location_element.text().next().unwrap_or_default();
+
+        let (flag_img_url, country, continent) = if let Some(location_img) = location_element.select(&img_selector).next() {
+            let flag_img_src = ctx.attr(location_img, "src")?;
+
+            let captures = ctx.captures(&re_location, ctx.attr(location_img, "title")?)?;
+            (flag_img_src.to_string(), captures[1].trim().to_string(), captures[2].trim().to_string())
+        } else {
+            ("".to_string(), "".to_string(), "".to_string())
+        };
+
+        Ok(CompetitionRegistration {
+            participant_id,
+            name: text,
+            date,
+            location: CompetitionLocation {
+                country,
+                continent,
+                flag_img_url,
+                place: place.trim().to_string()
+            }
+        })
+    };
 
     let mut participated_in = Vec::new();
 
     if let Some(competitions_table) = element.select(&competition_list_selector).next() {
-        for row in competitions_table.select(&competition_list_row_selector) {
-            let link = row.select(&competition_list_link_selector).next().unwrap();
-            //dbg!(row.html());
-            let date: String = row.select(&competition_list_sortdata_selector).next()
-                .unwrap()
-                .value().attr("data").unwrap()
-                // we want to drop the location from the string so keep only digits
-                .chars().filter(|v| v.is_ascii_digit()).collect();
-
-            let date = NaiveDate::parse_from_str(&date, "%Y%m%d").unwrap();
-
-            let text = link.text().filter(|v| {
-                !v.trim().is_empty()
-            }).next().unwrap().trim().to_string();
-            let participant_id = if let Some(v) = link.select(&a_selector).next() {
-                // if there is a child, it will be <a> with a link to the competition
-               let s = v.value().attr("href").unwrap();
-                re_participant.captures_iter(s).next().unwrap()[1].parse().unwrap()
-            } else { 0 };
-
-            let location_element = row.select(&competition_list_location_selector).next().unwrap();
-            let place = location_element.text().next().unwrap();
-
-            let (flag_img_url, country, continent) = if let Some(location_img) = location_element.select(&img_selector).next() {
-                let flag_img_src = location_img.value().attr("src").unwrap();
-
-                let captures = re_location.captures_iter(location_img.value().attr("title").unwrap()).next().unwrap();
-                (flag_img_src.to_string(), captures[1].trim().to_string(), captures[2].trim().to_string())
-            } else {
-                ("".to_string(), "".to_string(), "".to_string())
-            };
-
-
-            participated_in.push(CompetitionRegistration {
-                participant_id,
-                name: text,
-                date,
-                location: CompetitionLocation {
-                    country,
-                    continent,
-                    flag_img_url,
-                    place: place.trim().to_string()
-                }
-            });
+        for (idx, row) in competitions_table.select(&competition_list_row_selector).enumerate() {
+            match parse_row(ctx.row(idx), row) {
+                Ok(v) => participated_in.push(v),
+                Err(e) => warnings.skip_row(e, &row.html())?,
+            }
         }
     }
 
     Ok(participated_in)
-}
\ No newline at end of file
+}
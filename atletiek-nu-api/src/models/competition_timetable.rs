@@ -0,0 +1,140 @@
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use chrono_tz::Tz;
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use crate::components::table::cell_text;
+use crate::error::ParseDetail;
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+use crate::event::Event;
+
+const MODEL: &str = "competition_timetable";
+
+const REGEX_COMPETITION_ID: &str = r#"wedstrijd/main/([0-9]{1,})/"#;
+
+/// Timezone the site shows its times in, athletics.app is a Dutch site
+pub const TIMEZONE: Tz = chrono_tz::Europe::Amsterdam;
+
+/// Every scheduled event of a competition, `wedstrijd/chronoloog/{id}/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompetitionTimetable {
+    pub competition_id: u32,
+    pub name: String,
+    /// In chronological order
+    pub days: Vec<TimetableDay>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimetableDay {
+    pub date: NaiveDate,
+    /// e.g. `Saturday 14 September`, only multi-day competitions have these
+    pub label: Option<String>,
+    pub events: Vec<ScheduledEvent>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScheduledEvent {
+    /// Local time, see [`TIMEZONE`]
+    pub time: DateTime<FixedOffset>,
+    /// e.g. `100m`
+    pub event_short: String,
+    /// e.g. `100 meters`
+    pub event_name: String,
+    pub start_group_name: String,
+    /// e.g. `Heats` or `Final`
+    pub round: Option<String>,
+    /// Use with [`crate::AtnClient::get_start_list_from_url`]
+    pub startlist_url: String,
+}
+
+impl ScheduledEvent {
+    /// From the short name, e.g. `60mH`
+    pub fn canonical_event(&self) -> Event {
+        Event::parse(&self.event_short)
+    }
+}
+
+/// Expects the DESKTOP site
+pub fn parse(html: Html) -> crate::Result<CompetitionTimetable> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
+
+/// Expects the DESKTOP site
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<CompetitionTimetable>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<CompetitionTimetable> {
+    let name_selector = Sel::new("div.pageTitle");
+    let competition_selector = Sel::new("div#menubottom > a.hidden-xs");
+    let row_selector = Sel::new("table.chronoloogtabel > tbody > tr");
+    let td_selector = Sel::new("td");
+    let a_selector = Sel::new("a");
+    let data_span_selector = Sel::new("span.sortData");
+    let visible_xs_inline_selector = Sel::new("span.visible-xs-inline");
+    let hidden_xs_selector = Sel::new("span.hidden-xs");
+    let re_competition_id = Re::new(REGEX_COMPETITION_ID);
+    let ctx = ParseCtx::new(MODEL);
+
+    let name = ctx.text(ctx.select_first(html.root_element(), &name_selector)?, "name")?.to_string();
+    let competition_url = ctx.attr(ctx.select_first(html.root_element(), &competition_selector)?, "href")?;
+    let competition_id = ctx.value("competition id", &ctx.captures(&re_competition_id, competition_url)?[1])?;
+
+    let parse_row = |ctx: ParseCtx, cells: Vec<ElementRef>| -> crate::Result<ScheduledEvent> {
+        if cells.len() < 3 {
+            return Err(ctx.error(ParseDetail::MissingElement { selector: td_selector.css() }));
+        }
+
+        let timestamp_text = ctx.attr(ctx.select_first(cells[0], &data_span_selector)?, "data")?;
+        let time = DateTime::from_timestamp(ctx.value("time", timestamp_text)?, 0)
+            .ok_or_else(|| ctx.invalid("time", timestamp_text, "out of range"))?
+            .with_timezone(&TIMEZONE)
+            .fixed_offset();
+        let startlist_url = ctx.attr(ctx.select_first(cells[0], &a_selector)?, "href")?.to_string();
+        let start_group_name = ctx.text(ctx.select_first(ctx.select_first(cells[1], &a_selector)?, &hidden_xs_selector)?, "start group")?.to_string();
+        let event_a = ctx.select_first(cells[2], &a_selector)?;
+
+        Ok(ScheduledEvent {
+            time,
+            event_short: ctx.text(ctx.select_first(event_a, &visible_xs_inline_selector)?, "event short name")?.to_string(),
+            event_name: ctx.text(ctx.select_first(event_a, &hidden_xs_selector)?, "event name")?.to_string(),
+            start_group_name,
+            round: cells.get(3).copied().and_then(cell_text),
+            startlist_url,
+        })
+    };
+
+    let mut days: Vec<TimetableDay> = Vec::new();
+    // set by a day header, the next event decides the date of the day
+    let mut label = None;
+    for (row_idx, row) in html.select(&row_selector).enumerate() {
+        let cells: Vec<_> = row.select(&td_selector).collect();
+        if cells.len() == 1 {
+            // multi-day competitions have a row with only the day before the events of that day
+            label = cell_text(cells[0]);
+            continue;
+        }
+
+        let event = match parse_row(ctx.row(row_idx), cells) {
+            Ok(v) => v,
+            Err(e) => {
+                warnings.skip_row(e, &row.html())?;
+                continue;
+            }
+        };
+
+        let date = event.time.date_naive();
+        match days.last_mut() {
+            // without day headers the date of the events splits the days
+            Some(day) if label.is_none() && (day.label.is_some() || day.date == date) => day.events.push(event),
+            _ => days.push(TimetableDay { date, label: label.take(), events: vec![event] }),
+        }
+    }
+
+    Ok(CompetitionTimetable {
+        competition_id,
+        name,
+        days,
+    })
+}
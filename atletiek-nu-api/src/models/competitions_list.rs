@@ -1,8 +1,10 @@
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
 use crate::util::clean_html;
-use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html};
 use serde::{Deserialize, Serialize};
 
+const MODEL: &'static str = "competitions_list";
+
 // Captures the amount of registrations in the first capture group
 const REGEX_REGISTRATIONS: &'static str = "([0-9]{1,}) registrations";
 
@@ -19,77 +21,87 @@ pub struct CompetitionsListElement {
     pub world_athletics_recognized: bool,
 }
 
-pub fn parse(html: Html) -> anyhow::Result<CompetitionsList> {
-    let selector = Selector::parse("div.competitions-list").unwrap();
+pub fn parse(html: Html) -> crate::Result<CompetitionsList> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
+
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<CompetitionsList>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<CompetitionsList> {
+    let selector = Sel::new("div.competitions-list");
 
     let mut res = Vec::new();
 
     for i in html.select(&selector) {
         let html = Html::parse_fragment(&i.inner_html());
-        res.extend(parse_element_list(html)?);
+        res.extend(parse_element_list_with(html, warnings)?);
     }
 
     Ok(res)
 }
 
-pub fn parse_element_list(html: Html) -> anyhow::Result<Vec<CompetitionsListElement>> {
-    let mut res = Vec::new();
-
-    let selector = Selector::parse("li").unwrap();
-    let a_selector = Selector::parse("a").unwrap();
-    let info_selector = Selector::parse("div.item-inner > div.item-title").unwrap();
-    let info_title_selector = Selector::parse("h6").unwrap();
-    let info_location_selector = Selector::parse("div.subtitle").unwrap();
-    let info_registrations_selector = Selector::parse("div.item-footer").unwrap();
-    let club_only_selector = Selector::parse("span.clubmembersonly").unwrap();
-    let wa_recognized_selector = Selector::parse("img.WA-label").unwrap();
+pub fn parse_element_list(html: Html) -> crate::Result<Vec<CompetitionsListElement>> {
+    parse_element_list_with(html, &mut Warnings::new(ParseMode::Strict))
+}
 
-    for i in html.select(&selector) {
-        let id = i
-            .select(&a_selector)
-            .next()
-            .unwrap()
+pub(crate) fn parse_element_list_with(html: Html, warnings: &mut Warnings) -> crate::Result<Vec<CompetitionsListElement>> {
+    let selector = Sel::new("li");
+    let a_selector = Sel::new("a");
+    let info_selector = Sel::new("div.item-inner > div.item-title");
+    let info_title_selector = Sel::new("h6");
+    let info_location_selector = Sel::new("div.subtitle");
+    let info_registrations_selector = Sel::new("div.item-footer");
+    let club_only_selector = Sel::new("span.clubmembersonly");
+    let wa_recognized_selector = Sel::new("img.WA-label");
+    let registrations_re = Re::new(REGEX_REGISTRATIONS);
+    let ctx = ParseCtx::new(MODEL);
+
+    let parse_row = |ctx: ParseCtx, i: ElementRef| -> crate::Result<CompetitionsListElement> {
+        let id = ctx
+            .select_first(i, &a_selector)?
             .value()
             .attr("href")
-            .and_then(|v| Some(v.replace("/event&do=get&event_id=", "")))
-            .unwrap_or("".to_string());
+            .map(|v| v.replace("/event&do=get&event_id=", ""))
+            .unwrap_or_default();
 
-        let info = i.select(&info_selector).next().unwrap();
-        let title_element = &info.select(&info_title_selector).next().unwrap();
-        let title = title_element.text().find(|v| !v.trim().is_empty()).unwrap();
+        let info = ctx.select_first(i, &info_selector)?;
+        let title_element = ctx.select_first(info, &info_title_selector)?;
+        let title = ctx.text(title_element, "title")?;
         let world_athletics_recognized = title_element
             .select(&wa_recognized_selector)
             .next()
             .is_some();
 
-        let location_element = info.select(&info_location_selector).next().unwrap();
+        let location_element = ctx.select_first(info, &info_location_selector)?;
         let location = clean_html(&location_element.inner_html());
         let club_only = location_element
             .select(&club_only_selector)
             .next()
             .is_some();
 
-        let registrations = info
-            .select(&info_registrations_selector)
-            .next()
-            .unwrap()
-            .inner_html();
+        let registrations = ctx.select_first(info, &info_registrations_selector)?.inner_html();
+        let registrations = ctx.captures(&registrations_re, &registrations)?[1].to_string();
 
-        let registrations_re = Regex::new(REGEX_REGISTRATIONS).unwrap();
-        let registrations = registrations_re
-            .captures_iter(&registrations)
-            .next()
-            .unwrap()[1]
-            .to_string();
-
-        res.push(CompetitionsListElement {
+        Ok(CompetitionsListElement {
             club_only,
             world_athletics_recognized,
-            id: id.parse()?,
-            name: title.trim().to_string(),
+            id: ctx.value("competition id", &id)?,
+            name: title.to_string(),
             location: location.trim().to_string(),
-            registrations: registrations.parse()?,
-        });
+            registrations: ctx.value("registrations", &registrations)?,
+        })
+    };
+
+    let mut res = Vec::new();
+    for (idx, i) in html.select(&selector).enumerate() {
+        match parse_row(ctx.row(idx), i) {
+            Ok(v) => res.push(v),
+            Err(e) => warnings.skip_row(e, &i.html())?,
+        }
     }
 
     Ok(res)
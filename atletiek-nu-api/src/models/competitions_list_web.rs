@@ -1,8 +1,9 @@
 use chrono::NaiveDate;
-use log::warn;
-use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html};
 use serde::{Deserialize, Serialize};
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+
+const MODEL: &'static str = "competitions_list_web";
 
 const REGEX_COMPETITION_ID: &'static str = r#"(\d{1,})"#;
 // 1: day of month, 2: month (MAR, AUG, etc.), 3: year
@@ -20,78 +21,96 @@ pub struct CompetitionsListWebElement {
     pub results_availible: bool,
     pub club_members_only: bool,
     pub competition_id: u32,
+    /// Only known when the list came from a search, the page itself doesn't show it
+    #[serde(default)]
+    pub kind: Option<CompetitionKind>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum CompetitionKind {
+    Indoor,
+    Outdoor,
+    CrossCountry,
+    Road,
 }
 
-pub fn parse(html: Html) -> anyhow::Result<CompetitionsWebList> {
-    let row_selector = Selector::parse("tr[onclick]").unwrap();
-    let date_selector = Selector::parse("td.datumCol > span.hidden-xs").unwrap();
-    let name_selector = Selector::parse("td.eventnaam > a > span > span.eventnaam").unwrap();
-    let location_selector =
-        Selector::parse("td.eventnaam > a > span > span.verenigingnaam").unwrap();
-    let registrations_selector =
-        Selector::parse("td.eventnaam > a > span > span.aantaldeelnemers").unwrap();
-    let status_selector = Selector::parse("td:last-child > span").unwrap();
-    let id_re = Regex::new(REGEX_COMPETITION_ID).unwrap();
-    let date_re = Regex::new(REGEX_DATE).unwrap();
-    let registrations_re = Regex::new(REGEX_REGISTRATIONS).unwrap();
+impl CompetitionKind {
+    pub const ALL: [CompetitionKind; 4] = [Self::Indoor, Self::Outdoor, Self::CrossCountry, Self::Road];
 
-    let mut result = Vec::new();
+    /// Value of the `event_soort[]` search parameter
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Indoor => "in",
+            Self::Outdoor => "out",
+            Self::CrossCountry => "cross",
+            Self::Road => "weg",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.code().eq_ignore_ascii_case(code.trim()))
+    }
+}
 
-    for i in html.select(&row_selector) {
-        let onclick = i.value().attr("onclick").unwrap();
-        let id: u32 = id_re.captures_iter(onclick).next().unwrap()[1]
-            .to_string()
-            .parse()?;
+pub fn parse(html: Html) -> crate::Result<CompetitionsWebList> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
+
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<CompetitionsWebList>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<CompetitionsWebList> {
+    let row_selector = Sel::new("tr[onclick]");
+    let date_selector = Sel::new("td.datumCol > span.hidden-xs");
+    let name_selector = Sel::new("td.eventnaam > a > span > span.eventnaam");
+    let location_selector = Sel::new("td.eventnaam > a > span > span.verenigingnaam");
+    let registrations_selector = Sel::new("td.eventnaam > a > span > span.aantaldeelnemers");
+    let status_selector = Sel::new("td:last-child > span");
+    let id_re = Re::new(REGEX_COMPETITION_ID);
+    let date_re = Re::new(REGEX_DATE);
+    let registrations_re = Re::new(REGEX_REGISTRATIONS);
+    let ctx = ParseCtx::new(MODEL);
+
+    let parse_row = |ctx: ParseCtx, i: ElementRef, warnings: &mut Warnings| -> crate::Result<CompetitionsListWebElement> {
+        let onclick = ctx.attr(i, "onclick")?;
+        let id: u32 = ctx.value("competition id", &ctx.captures(&id_re, onclick)?[1])?;
 
         let date = match i.select(&date_selector).next() {
             Some(element) => {
                 let date_text = element.inner_html();
-                let date_captures = date_re.captures_iter(&date_text).next().unwrap();
-
-                let day = date_captures[1].parse()?;
-                let month = match &date_captures[2] {
-                    "JAN" => 1,
-                    "FEB" => 2,
-                    "MAR" => 3,
-                    "APR" => 4,
-                    "MAY" => 5,
-                    "JUN" => 6,
-                    "JUL" => 7,
-                    "AUG" => 8,
-                    "SEP" => 9,
-                    "OCT" => 10,
-                    "NOV" => 11,
-                    "DEC" => 12,
-                    _ => anyhow::bail!("Invalid month: {}", &date_captures[2]),
-                };
-                let year = date_captures[3].parse()?;
-                NaiveDate::from_ymd_opt(year, month, day).unwrap()
+                let date_captures = ctx.captures(&date_re, &date_text)?;
+
+                let day = ctx.value("day", &date_captures[1])?;
+                let month = crate::components::date::month(&date_captures[2])
+                    .ok_or_else(|| ctx.invalid("month", &date_captures[2], "unknown month"))?;
+                let year = ctx.value("year", &date_captures[3])?;
+                NaiveDate::from_ymd_opt(year, month, day)
+                    .ok_or_else(|| ctx.invalid("date", &date_text, "out of range"))?
             }
             // Assume that it's today as there is no date
             None => {
-                warn!("No date found, assuming today");
+                warnings.warn(&ctx, "date", "No date found, assuming today", &i.html());
                 chrono::offset::Local::now().date_naive()
             },
         };
 
-        let name_node = i.select(&name_selector).next().unwrap();
+        let name_node = ctx.select_first(i, &name_selector)?;
         let club_members_only = name_node.inner_html().contains("Club members only");
         let name = name_node
             .text()
             .next()
-            .unwrap()
+            .unwrap_or_default()
             .replace("\u{a0}", "")
             .trim()
             .to_string();
 
-        let location = i.select(&location_selector).next().unwrap().inner_html();
+        let location = ctx.select_first(i, &location_selector)?.inner_html();
         let registrations = {
-            let text = i
-                .select(&registrations_selector)
-                .next()
-                .unwrap()
-                .inner_html();
-            registrations_re.captures_iter(&text).next().unwrap()[1].parse()?
+            let text = ctx.select_first(i, &registrations_selector)?.inner_html();
+            ctx.value("registrations", &ctx.captures(&registrations_re, &text)?[1])?
         };
         let results_availible = {
             if let Some(text) = i.select(&status_selector).next() {
@@ -101,12 +120,12 @@ pub fn parse(html: Html) -> anyhow::Result<CompetitionsWebList> {
                     false
                 }
             } else {
-                warn!("Status selector none, assuming no results");
+                warnings.warn(&ctx, "status", "No status found, assuming no results", &i.html());
                 false
             }
         };
 
-        result.push(CompetitionsListWebElement {
+        Ok(CompetitionsListWebElement {
             name,
             date,
             registrations,
@@ -114,7 +133,16 @@ pub fn parse(html: Html) -> anyhow::Result<CompetitionsWebList> {
             competition_id: id,
             club_members_only,
             results_availible,
+            kind: None,
         })
+    };
+
+    let mut result = Vec::new();
+    for (idx, i) in html.select(&row_selector).enumerate() {
+        match parse_row(ctx.row(idx), i, warnings) {
+            Ok(v) => result.push(v),
+            Err(e) => warnings.skip_row(e, &i.html())?,
+        }
     }
 
     Ok(result)
@@ -0,0 +1,257 @@
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use crate::components::table::{cell_text, Headers};
+use crate::error::{AtnError, ParseDetail};
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+use crate::performance::{Performance, PerformanceKind, ResultStatus};
+use crate::event::Event;
+
+const MODEL: &str = "event_results";
+
+const REGEX_COMPETITION_ID: &str = r#"wedstrijd/main/([0-9]{1,})/"#;
+const REGEX_PARTICIPANT_ID: &str = r#"atleet/main/([0-9]{1,})/"#;
+
+/// Every round and heat of one event at a competition, `wedstrijd/uitslagenonderdeel/{id}/{event}/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventResults {
+    pub competition_id: u32,
+    pub event_name: String,
+    pub rounds: Vec<EventRound>,
+}
+
+impl EventResults {
+    /// From the name in the header
+    pub fn canonical_event(&self) -> Event {
+        Event::parse(&self.event_name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRound {
+    /// e.g. `Heats` or `Final`, empty if the page doesn't split the event in rounds
+    pub name: String,
+    pub heats: Vec<Heat>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Heat {
+    /// e.g. `Heat 1`, empty if the round has a single heat without a name
+    pub name: String,
+    /// Wind of the whole heat, for sprints
+    pub wind_speed: Option<f32>,
+    pub results: Vec<EventResultRow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EventResultRow {
+    pub place: Option<u16>,
+    pub lane: Option<u16>,
+    pub bib: Option<String>,
+    pub participant_id: Option<u32>,
+    pub name: String,
+    pub club: Option<String>,
+    pub category: Option<String>,
+    /// `None` if there's a status instead
+    pub performance: Option<Performance>,
+    /// The result as shown on the site, e.g. `11,92` or `DNS`
+    pub performance_text: String,
+    /// Wind of this athlete's result, for jumps
+    pub wind_speed: Option<f32>,
+    /// Seconds
+    pub reaction_time: Option<f32>,
+    /// e.g. `DNS`, `DNF` or `DQ (TR16.8)`
+    pub status: Option<ResultStatus>,
+}
+
+/// Index of every column in a results table, if the page has it
+struct Columns {
+    place: Option<usize>,
+    lane: Option<usize>,
+    bib: Option<usize>,
+    name: Option<usize>,
+    club: Option<usize>,
+    category: Option<usize>,
+    result: Option<usize>,
+    wind: Option<usize>,
+    reaction: Option<usize>,
+}
+
+impl Columns {
+    fn new(headers: &Headers) -> Self {
+        Self {
+            place: headers.find(&["Pos.", "Place"]),
+            lane: headers.find(&["Lane", "Order"]),
+            bib: headers.find(&["Bib"]),
+            name: headers.find(&["Name"]),
+            club: headers.find(&["Club"]),
+            category: headers.find(&["Cat.", "Category"]),
+            result: headers.find(&["Result", "Perf."]),
+            wind: headers.find(&["Wind"]),
+            reaction: headers.find(&["Reaction", "RT"]),
+        }
+    }
+}
+
+/// Expects the DESKTOP site
+pub fn parse(html: Html) -> crate::Result<EventResults> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
+
+/// Expects the DESKTOP site
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<EventResults>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<EventResults> {
+    let name_selector = Sel::new("div.pageTitle");
+    let competition_selector = Sel::new("div#menubottom > a.hidden-xs");
+    let container_selector = Sel::new("div.uitslagen");
+    let wind_selector = Sel::new("span.wind");
+    let header_selector = Sel::new("thead > tr > th");
+    let row_selector = Sel::new("tbody > tr");
+    let td_selector = Sel::new("td");
+    let a_selector = Sel::new("a");
+    let data_span_selector = Sel::new("span.sortData");
+    let visible_span_selector = Sel::new("span.tipped");
+    let re_competition_id = Re::new(REGEX_COMPETITION_ID);
+    let re_participant_id = Re::new(REGEX_PARTICIPANT_ID);
+    let ctx = ParseCtx::new(MODEL);
+
+    let event_name = ctx.text(ctx.select_first(html.root_element(), &name_selector)?, "event name")?.to_string();
+    let competition_url = ctx.attr(ctx.select_first(html.root_element(), &competition_selector)?, "href")?;
+    let competition_id = ctx.value("competition id", &ctx.captures(&re_competition_id, competition_url)?[1])?;
+    let kind = PerformanceKind::from_event(&event_name);
+
+    let container = match html.select(&container_selector).next() {
+        Some(v) => v,
+        None => return Err(AtnError::NoResultsYet),
+    };
+
+    let parse_row = |ctx: ParseCtx, columns: &Columns, row: ElementRef, warnings: &mut Warnings| -> crate::Result<EventResultRow> {
+        let cells: Vec<ElementRef> = row.select(&td_selector).collect();
+        let cell = |column: Option<usize>| column.and_then(|v| cells.get(v)).copied();
+        let text = |column: Option<usize>| cell(column).and_then(cell_text);
+        let number = |field: &'static str, text: Option<String>| -> crate::Result<Option<f32>> {
+            text.map(|v| ctx.value(field, &v.replace(',', "."))).transpose()
+        };
+
+        let missing_td = || ctx.error(ParseDetail::MissingElement { selector: td_selector.css() });
+        let name_cell = cell(columns.name).ok_or_else(missing_td)?;
+        let name = ctx.text(name_cell, "name")?.replace("  ", " ");
+        let participant_id = match name_cell.select(&a_selector).next() {
+            Some(a) => Some(ctx.value("participant id", &ctx.captures(&re_participant_id, ctx.attr(a, "href")?)?[1])?),
+            None => None,
+        };
+
+        let result_cell = cell(columns.result).ok_or_else(missing_td)?;
+        let performance_text = match result_cell.select(&visible_span_selector).next() {
+            Some(v) => v.text().collect::<String>(),
+            None => result_cell.text().collect::<String>(),
+        }.trim().to_string();
+
+        // anything that doesn't start with a digit is a status like DNS, and the sortData holds a sentinel
+        let data = result_cell.select(&data_span_selector).next();
+        let status = match ResultStatus::parse(&performance_text) {
+            Some(v) => Some(v),
+            None => match data {
+                Some(data) => ResultStatus::from_sentinel(kind, ctx.value("performance", ctx.attr(data, "data")?)?),
+                None => None,
+            },
+        };
+        let performance = match (&status, data) {
+            (Some(_), _) => None,
+            // the text has the precision of a time, the data only as many digits as it needs
+            (None, data) => match Performance::parse(kind, &performance_text) {
+                Some(v) => Some(v),
+                None => match data {
+                    Some(data) => Performance::from_value(kind, ctx.value("performance", ctx.attr(data, "data")?)?),
+                    None => return Err(ctx.invalid("performance", &performance_text, "not a time, distance or points")),
+                },
+            },
+        };
+
+        let wind_speed = match cell(columns.wind) {
+            Some(v) => crate::components::wind_speed::parse(&v.text().collect::<String>()),
+            // jumps can have the wind in the result cell
+            None if performance_text.contains("m/s") => crate::components::wind_speed::parse(&performance_text),
+            None => None,
+        };
+
+        let place = match text(columns.place) {
+            Some(v) => match v.trim_end_matches('.').parse() {
+                Ok(v) => Some(v),
+                Err(e) => {
+                    warnings.warn(&ctx, "place", format!("Failed to parse place {}: {}", v, e), &row.html());
+                    None
+                }
+            },
+            None => None,
+        };
+
+        Ok(EventResultRow {
+            place,
+            lane: text(columns.lane).map(|v| ctx.value("lane", &v)).transpose()?,
+            bib: text(columns.bib),
+            participant_id,
+            name,
+            club: text(columns.club),
+            category: text(columns.category),
+            performance,
+            performance_text,
+            wind_speed,
+            reaction_time: number("reaction time", text(columns.reaction))?,
+            status,
+        })
+    };
+
+    let mut rounds: Vec<EventRound> = Vec::new();
+    // the rounds, heats and tables follow each other in the container, in that order
+    for element in container.children().filter_map(ElementRef::wrap) {
+        match element.value().name() {
+            "h2" => rounds.push(EventRound {
+                name: element.text().collect::<String>().trim().to_string(),
+                heats: Vec::new(),
+            }),
+            "h3" => {
+                if rounds.is_empty() {
+                    rounds.push(EventRound { name: String::new(), heats: Vec::new() });
+                }
+
+                let wind_speed = element
+                    .select(&wind_selector)
+                    .next()
+                    .and_then(|v| crate::components::wind_speed::parse(&v.text().collect::<String>()));
+                let name = element.text().next().unwrap_or_default().trim().to_string();
+                rounds.last_mut().unwrap().heats.push(Heat { name, wind_speed, results: Vec::new() });
+            }
+            "table" => {
+                if rounds.is_empty() {
+                    rounds.push(EventRound { name: String::new(), heats: Vec::new() });
+                }
+                let round = rounds.last_mut().unwrap();
+                if round.heats.is_empty() {
+                    round.heats.push(Heat { name: String::new(), wind_speed: None, results: Vec::new() });
+                }
+                let heat = round.heats.last_mut().unwrap();
+
+                let columns = Columns::new(&Headers::new(element, &header_selector));
+
+                for (row_idx, row) in element.select(&row_selector).enumerate() {
+                    match parse_row(ctx.row(row_idx), &columns, row, warnings) {
+                        Ok(v) => heat.results.push(v),
+                        Err(e) => warnings.skip_row(e, &row.html())?,
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(EventResults {
+        competition_id,
+        event_name,
+        rounds,
+    })
+}
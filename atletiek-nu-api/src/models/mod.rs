@@ -6,3 +6,9 @@ pub mod registrations_list;
 pub mod registrations_list_web;
 pub mod athlete_profile;
 pub mod competition_registrations_list;
+pub mod competition_details;
+pub mod event_results;
+pub mod start_list;
+pub mod competition_timetable;
+pub mod relay_team;
+pub mod club;
@@ -1,6 +1,9 @@
-use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::{ElementRef, Html};
 use serde::{Deserialize, Serialize};
+use crate::error::ParseDetail;
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+
+const MODEL: &'static str = "registrations_list";
 
 // Captures the ID in the first capture group
 const REGEX_PARTICIPANT_ID: &'static str = r#"deelnemer_id=([\d]{1,})"#;
@@ -21,31 +24,40 @@ pub struct RegistrationsListElement {
     pub out_of_competition: bool
 }
 
-pub fn parse(html: Html) -> anyhow::Result<RegistrationsList> {
-    let script_selector = Selector::parse("script.list-content-registrations").unwrap();
-    let selector = Selector::parse("li > a").unwrap();
-    let info_selector = Selector::parse("div.item-inner > div.item-title").unwrap();
-    let event_selector = Selector::parse("div.item-inner > div.item-after").unwrap();
-    let re_participant_id = Regex::new(REGEX_PARTICIPANT_ID).unwrap();
-    let re_cat_and_club = Regex::new(REGEX_CATEGORY_AND_CLUB).unwrap();
-    let re_cat_club_and_team = Regex::new(REGEX_CATEGORY_CLUB_AND_TEAM).unwrap();
-
-    let script = html
-        .select(&script_selector)
-        .next()
-        .unwrap()
+pub fn parse(html: Html) -> crate::Result<RegistrationsList> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
+
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<RegistrationsList>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<RegistrationsList> {
+    let script_selector = Sel::new("script.list-content-registrations");
+    let selector = Sel::new("li > a");
+    let info_selector = Sel::new("div.item-inner > div.item-title");
+    let event_selector = Sel::new("div.item-inner > div.item-after");
+    let re_participant_id = Re::new(REGEX_PARTICIPANT_ID);
+    let re_cat_and_club = Re::new(REGEX_CATEGORY_AND_CLUB);
+    let re_cat_club_and_team = Re::new(REGEX_CATEGORY_CLUB_AND_TEAM);
+    let ctx = ParseCtx::new(MODEL);
+
+    let script = ctx
+        .select_first(html.root_element(), &script_selector)?
         .inner_html()
         .replace("&lt;", "<")
         .replace("&gt;", ">");
 
-    let mut res = Vec::new();
-    for i in Html::parse_fragment(&script).select(&selector) {
-        let info_element = i.select(&info_selector).next().unwrap();
-        let event_element = i.select(&event_selector).next().unwrap();
+    let fragment = Html::parse_fragment(&script);
+    let parse_row = |ctx: ParseCtx, i: ElementRef| -> crate::Result<RegistrationsListElement> {
+        let info_element = ctx.select_first(i, &info_selector)?;
+        let event_element = ctx.select_first(i, &event_selector)?;
 
-        let onclick = i.value().attr("onclick").unwrap();
+        let onclick = ctx.attr(i, "onclick")?;
         let participant_id: u32 =
-            re_participant_id.captures_iter(onclick).next().unwrap()[1].parse()?;
+            ctx.value("participant id", &ctx.captures(&re_participant_id, onclick)?[1])?;
 
         let info_texts: Vec<&str> = info_element
             .text()
@@ -53,21 +65,27 @@ pub fn parse(html: Html) -> anyhow::Result<RegistrationsList> {
             .filter(|v| !v.is_empty())
             .collect();
 
+        if info_texts.len() < 2 {
+            return Err(ctx.error(ParseDetail::MissingText { field: "category and club" }));
+        }
+
         let name = info_texts[0].trim().replace("  ", " ");
         let ooc = info_texts[1] == "(OoC)";
 
         // 1 if not OoC and 2 if OoC
-        let cat_and_club = info_texts[ooc as usize + 1];
+        let cat_and_club = *info_texts
+            .get(ooc as usize + 1)
+            .ok_or_else(|| ctx.error(ParseDetail::MissingText { field: "category and club" }))?;
 
         let (category, club_name, mut team_name) =
-            match re_cat_club_and_team.captures_iter(&cat_and_club).next() {
+            match re_cat_club_and_team.captures(cat_and_club) {
                 Some(x) => (
                     x[1].trim().replace("  ", " ").to_string(),
                     x[2].trim().replace("  ", " ").to_string(),
                     Some(x[3].trim().replace("  ", " ").to_string()),
                 ),
                 None => {
-                    let captures = re_cat_and_club.captures_iter(&cat_and_club).next().unwrap();
+                    let captures = ctx.captures(&re_cat_and_club, cat_and_club)?;
                     (
                         captures[1].trim().replace("  ", " ").to_string(),
                         captures[2]
@@ -91,7 +109,7 @@ pub fn parse(html: Html) -> anyhow::Result<RegistrationsList> {
             .map(|v| v.to_string())
             .collect();
 
-        res.push(RegistrationsListElement {
+        Ok(RegistrationsListElement {
             participant_id,
             name: name.to_string(),
             category,
@@ -100,6 +118,14 @@ pub fn parse(html: Html) -> anyhow::Result<RegistrationsList> {
             events,
             out_of_competition: ooc
         })
+    };
+
+    let mut res = Vec::new();
+    for (idx, i) in fragment.select(&selector).enumerate() {
+        match parse_row(ctx.row(idx), i) {
+            Ok(v) => res.push(v),
+            Err(e) => warnings.skip_row(e, &i.html())?,
+        }
     }
 
     Ok(res)
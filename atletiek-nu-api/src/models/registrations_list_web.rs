@@ -1,10 +1,15 @@
-use log::{error, trace, warn};
-use regex::Regex;
-use scraper::{Html, Selector};
+use log::{error, trace};
+use scraper::{ElementRef, Html};
 use serde::{Deserialize, Serialize};
+use crate::error::ParseDetail;
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+use crate::event::Event;
+
+const MODEL: &'static str = "registrations_list_web";
 
 const REGEX_PARTICIPANT_ID: &'static str = r#"deelnemer_id=([0-9]{0,})"#;
 const REGEX_CATEGORY_AND_CLUB: &'static str = r#"([\s\S]{1,}) - ([\s\S]{1,})"#;
+const REGEX_CLUB_ID: &'static str = r#"vereniging/main/([0-9]{1,})/"#;
 const REGEX_RELAY_PARTICIPANT_ID: &'static str = r#"https://www.athletics.app/estafetteteam/main/(\d{1,})/"#;
 
 pub type RegistrationsWebList = Vec<RegistrationsWebListElement>;
@@ -16,6 +21,9 @@ pub struct RegistrationsWebListElement {
     pub category: String,
     pub short_club_name: String,
     pub club_name: String,
+    /// See [`crate::AtnClient::get_club`]
+    #[serde(default)]
+    pub club_id: Option<u32>,
     pub team_name: Option<String>,
     pub relay_teams: Vec<RelayTeam>,
     pub events: Vec<(String, EventStatus)>,
@@ -23,6 +31,13 @@ pub struct RegistrationsWebListElement {
     pub bib_number: Option<u32>
 }
 
+impl RegistrationsWebListElement {
+    /// The events from [`Self::events`], the codes like `400m_f` parsed with [`Event::parse`]
+    pub fn canonical_events(&self) -> Vec<(Event, &EventStatus)> {
+        self.events.iter().map(|(name, status)| (Event::parse(name), status)).collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RelayTeam {
     pub participant_id: u32,
@@ -45,35 +60,45 @@ pub enum EventStatus {
     Unexpected(String),
 }
 
-pub fn parse(html: Html) -> anyhow::Result<RegistrationsWebList> {
-    let table_selector = Selector::parse("table.deelnemerstabel").unwrap();
-    let th_selector = Selector::parse("thead > tr > th").unwrap();
-    let tr_selector = Selector::parse("tbody > tr").unwrap();
-    let td_selector = Selector::parse("td").unwrap();
-    let span_selector = Selector::parse("span").unwrap();
-    let a_selector = Selector::parse("a").unwrap();
-    let span_detail_selector = Selector::parse("span.deelnemer-smalldetail").unwrap();
-    let span_tipped_selector = Selector::parse("span.tipped").unwrap();
-    let re_participant = Regex::new(REGEX_PARTICIPANT_ID).unwrap();
-    let re_cat_club = Regex::new(REGEX_CATEGORY_AND_CLUB).unwrap();
-    let re_relay_participant = Regex::new(REGEX_RELAY_PARTICIPANT_ID).unwrap();
-
-    let table = html.select(&table_selector).next().unwrap();
+pub fn parse(html: Html) -> crate::Result<RegistrationsWebList> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
+
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<RegistrationsWebList>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<RegistrationsWebList> {
+    let table_selector = Sel::new("table.deelnemerstabel");
+    let th_selector = Sel::new("thead > tr > th");
+    let tr_selector = Sel::new("tbody > tr");
+    let td_selector = Sel::new("td");
+    let span_selector = Sel::new("span");
+    let a_selector = Sel::new("a");
+    let span_detail_selector = Sel::new("span.deelnemer-smalldetail");
+    let span_tipped_selector = Sel::new("span.tipped");
+    let re_participant = Re::new(REGEX_PARTICIPANT_ID);
+    let re_cat_club = Re::new(REGEX_CATEGORY_AND_CLUB);
+    let re_club = Re::new(REGEX_CLUB_ID);
+    let re_relay_participant = Re::new(REGEX_RELAY_PARTICIPANT_ID);
+    let ctx = ParseCtx::new(MODEL);
+
+    let table = ctx.select_first(html.root_element(), &table_selector)?;
     let mut table_headers: Vec<String> = Vec::new();
 
     for item in table.select(&th_selector) {
-        let text = item.text().next().unwrap().to_string();
+        let text = item.text().next().unwrap_or_default().to_string();
         trace!("Got table header {}: {}", table_headers.len() + 1, &text);
         table_headers.push(text.to_lowercase());
     }
 
-    let mut res = Vec::new();
-
-    for row in table.select(&tr_selector) {
-        let participant_id_str = row.value().attr("id").expect("id attribute on row");
-        let captured_participant_id = re_participant.captures_iter(participant_id_str).next().expect("participant id in id attribute");
+    let parse_row = |ctx: ParseCtx, row: ElementRef, warnings: &mut Warnings| -> crate::Result<RegistrationsWebListElement> {
+        let participant_id_str = ctx.attr(row, "id")?;
+        let captured_participant_id = ctx.captures(&re_participant, participant_id_str)?;
         trace!("Captured participant id: {}", &captured_participant_id[1]);
-        let participant_id = captured_participant_id[1].parse()?;
+        let participant_id = ctx.value("participant id", &captured_participant_id[1])?;
 
         let mut item = RegistrationsWebListElement {
             participant_id,
@@ -81,6 +106,7 @@ pub fn parse(html: Html) -> anyhow::Result<RegistrationsWebList> {
             category: String::new(),
             short_club_name: String::new(),
             club_name: String::new(),
+            club_id: None,
             team_name: None,
             relay_teams: Vec::new(),
             events: Vec::new(),
@@ -89,12 +115,20 @@ pub fn parse(html: Html) -> anyhow::Result<RegistrationsWebList> {
         };
 
         for (i, element) in row.select(&td_selector).enumerate() {
-            match table_headers[i].as_str() {
+            let header = match table_headers.get(i) {
+                Some(header) => header.as_str(),
+                None => {
+                    warnings.warn(&ctx, "table header", "Row has more cells than the table has headers", &row.html());
+                    break;
+                }
+            };
+
+            match header {
                 "bib" => {
                     let text = match element.select(&span_selector).next() {
-                        Some(span) => span.text().next().unwrap(),
+                        Some(span) => span.text().next().unwrap_or_default(),
                         None => {
-                            warn!("Bib tr contained no span!");
+                            warnings.warn(&ctx, "bib", "Bib cell contained no span", &element.html());
                             continue;
                         },
                     };
@@ -102,60 +136,73 @@ pub fn parse(html: Html) -> anyhow::Result<RegistrationsWebList> {
                     match text.parse() {
                         Ok(v) => item.bib_number = Some(v),
                         Err(e) => {
-                            trace!("Failed to parse bib '{}': {}", element.select(&span_selector).next().unwrap().text().next().unwrap(), e)
+                            trace!("Failed to parse bib '{}': {}", text, e)
                         }
                     }
                 },
                 "name" => {
-                    let a = element.select(&a_selector).next().unwrap();
-                    let mut a_texts = a.text();
-                    let mut a_text = String::new();
+                    let a = ctx.select_first(element, &a_selector)?;
 
-                    while a_text.is_empty() || a_text.contains(".") {
-                        a_text = a_texts.next().unwrap().trim().replace("  ", " ");
-                        trace!("Got text (item.name) from name > a: {}", a_text);
-                    }
-                    item.name = a_text.to_string();
+                    // skip the initials
+                    item.name = a
+                        .text()
+                        .map(|v| v.trim().replace("  ", " "))
+                        .find(|v| !v.is_empty() && !v.contains("."))
+                        .ok_or_else(|| ctx.error(ParseDetail::MissingText { field: "name" }))?;
+                    trace!("Got text (item.name) from name > a: {}", item.name);
 
-                    let span_text = a.select(&span_detail_selector).next().unwrap().text().next().unwrap().trim();
+                    let span_text = ctx.text(ctx.select_first(a, &span_detail_selector)?, "category and club")?;
                     trace!("Got text from name > a > span: {}", span_text);
-                    let captures = re_cat_club.captures_iter(span_text).next().unwrap();
+                    let captures = ctx.captures(&re_cat_club, span_text)?;
 
                     item.category = captures[1].to_string();
                     item.short_club_name = captures[2].to_string().replace(" -", "");
 
                     match a.select(&span_tipped_selector).next() {
                         Some(span) => {
-                            let text = span.text().next().unwrap();
+                            let text = span.text().next().unwrap_or_default();
                             if text == "(OoC)" {
                                 trace!("Found (OoC)");
                                 item.out_of_competition = true;
                             } else {
-                                warn!("Got tipped span with text other than (OoC), got {}", text);
+                                warnings.warn(&ctx, "out of competition", format!("Got tipped span with text other than (OoC), got {}", text), &span.html());
                             }
                         },
                         None => (),
                     }
                 },
                 "club" | "team" => {
-                    let mut a_text_iter = match element.select(&a_selector).next() {
-                        Some(element) => element.text(),
+                    let a = match element.select(&a_selector).next() {
+                        Some(element) => element,
                         None => {
-                            warn!("club/team element contained no a!");
+                            warnings.warn(&ctx, header, "Cell contained no link", &element.html());
                             continue;
                         },
                     };
-                    let mut a_text = String::new();
 
-                    while a_text.is_empty() || a_text.contains("...") {
-                        a_text = a_text_iter.next().unwrap().trim().to_string();
-                        trace!("New a_text: {}", a_text);
-                    }
+                    // skip the shortened name
+                    let a_text = match a
+                        .text()
+                        .map(|v| v.trim().to_string())
+                        .find(|v| !v.is_empty() && !v.contains("...")) {
+                        Some(v) => v,
+                        None => {
+                            warnings.warn(&ctx, header, "Cell contained no text", &element.html());
+                            continue;
+                        }
+                    };
 
-                    trace!("table {} has value {}", table_headers[i], &a_text);
+                    trace!("table {} has value {}", header, &a_text);
 
-                    match table_headers[i].as_str() {
-                        "club" => item.club_name = a_text,
+                    match header {
+                        "club" => {
+                            item.club_name = a_text;
+                            item.club_id = a
+                                .value()
+                                .attr("href")
+                                .and_then(|v| re_club.captures(v))
+                                .and_then(|v| v[1].parse().ok());
+                        }
                         "team" => item.team_name = Some(a_text),
                         _ => error!("Should be unreachable!"),
                     }
@@ -167,7 +214,7 @@ pub fn parse(html: Html) -> anyhow::Result<RegistrationsWebList> {
                     while let Some(tipped_span) = tipped_spans.next() {
                         trace!("Scraping tipped span for events");
                         // we have tipped spans instead of normal text
-                        let kind = tipped_span.value().attr("title").unwrap().trim().to_lowercase();
+                        let kind = ctx.attr(tipped_span, "title")?.trim().to_lowercase();
                         let event_status = match kind.as_str() {
                             "unverified" => EventStatus::Unverified,
                             "cancelled" => EventStatus::Cancelled,
@@ -178,11 +225,11 @@ pub fn parse(html: Html) -> anyhow::Result<RegistrationsWebList> {
                             "verified" => EventStatus::Verified,
                             "in review" => EventStatus::InReview,
                             x => {
-                                error!("Unexpected event status: {}", x);
+                                warnings.warn(&ctx, "event status", format!("Unexpected event status: {}", x), &tipped_span.html());
                                 EventStatus::Unexpected(kind)
                             }
                         };
-                        let event_text = tipped_span.text().next().unwrap().trim();
+                        let event_text = ctx.text(tipped_span, "event")?;
                         events.push((event_text.to_string(), event_status));
                     }
 
@@ -204,9 +251,9 @@ pub fn parse(html: Html) -> anyhow::Result<RegistrationsWebList> {
                 "relay team" => {
                     let mut a = element.select(&a_selector);
                     while let Some(a) = a.next() {
-                        let href = a.value().attr("href").unwrap();
-                        let id = re_relay_participant.captures_iter(href).next().unwrap()[1].parse().unwrap();
-                        let text = a.text().next().unwrap().trim().to_string();
+                        let href = ctx.attr(a, "href")?;
+                        let id = ctx.value("relay participant id", &ctx.captures(&re_relay_participant, href)?[1])?;
+                        let text = a.text().next().unwrap_or_default().trim().to_string();
 
                         item.relay_teams.push(RelayTeam {
                             participant_id: id,
@@ -218,7 +265,15 @@ pub fn parse(html: Html) -> anyhow::Result<RegistrationsWebList> {
             }
         }
 
-        res.push(item);
+        Ok(item)
+    };
+
+    let mut res = Vec::new();
+    for (idx, row) in table.select(&tr_selector).enumerate() {
+        match parse_row(ctx.row(idx), row, warnings) {
+            Ok(v) => res.push(v),
+            Err(e) => warnings.skip_row(e, &row.html())?,
+        }
     }
 
     Ok(res)
@@ -0,0 +1,255 @@
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use crate::components::table::{cell_text, Headers};
+use crate::error::ParseDetail;
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+use crate::performance::{Performance, PerformanceKind, ResultStatus};
+
+const MODEL: &str = "relay_team";
+
+const REGEX_COMPETITION_ID: &str = r#"wedstrijd/main/([0-9]{1,})/"#;
+const REGEX_PARTICIPANT_ID: &str = r#"atleet/main/([0-9]{1,})/"#;
+const REGEX_CLUB_ID: &str = r#"vereniging/main/([0-9]{1,})/"#;
+
+/// A relay team at a competition, `estafetteteam/main/{id}/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayTeamDetails {
+    pub competition_id: u32,
+    /// e.g. `AV Gouda 1`
+    pub name: String,
+    pub club: Option<String>,
+    pub club_id: Option<u32>,
+    pub category: Option<String>,
+    pub event_name: Option<String>,
+    /// In leg order, followed by the reserves
+    pub members: Vec<RelayMember>,
+    pub results: Vec<RelayResult>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RelayMember {
+    /// `None` for a reserve
+    pub leg: Option<u8>,
+    pub bib: Option<String>,
+    pub participant_id: Option<u32>,
+    pub name: String,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RelayResult {
+    /// e.g. `Heats` or `Final`
+    pub round: Option<String>,
+    pub place: Option<u16>,
+    /// `None` if there's a status instead
+    pub performance: Option<Performance>,
+    /// The result as shown on the site, e.g. `48,71` or `DNF`
+    pub performance_text: String,
+    /// e.g. `DNS`, `DNF` or `DQ (TR24.19)`
+    pub status: Option<ResultStatus>,
+    /// Time at the end of every leg, empty if the site doesn't show them
+    pub splits: Vec<Performance>,
+}
+
+/// Index of every column in the members table, if the page has it
+struct MemberColumns {
+    leg: Option<usize>,
+    bib: Option<usize>,
+    name: Option<usize>,
+    category: Option<usize>,
+}
+
+impl MemberColumns {
+    fn new(headers: &Headers) -> Self {
+        Self {
+            leg: headers.find(&["Leg"]),
+            bib: headers.find(&["Bib"]),
+            name: headers.find(&["Name"]),
+            category: headers.find(&["Cat.", "Category"]),
+        }
+    }
+}
+
+/// Index of every column in the results table, if the page has it
+struct ResultColumns {
+    round: Option<usize>,
+    place: Option<usize>,
+    result: Option<usize>,
+    splits: Option<usize>,
+}
+
+impl ResultColumns {
+    fn new(headers: &Headers) -> Self {
+        Self {
+            round: headers.find(&["Round"]),
+            place: headers.find(&["Pos.", "Place"]),
+            result: headers.find(&["Result", "Perf."]),
+            splits: headers.find(&["Splits"]),
+        }
+    }
+}
+
+/// Expects the DESKTOP site
+pub fn parse(html: Html) -> crate::Result<RelayTeamDetails> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
+
+/// Expects the DESKTOP site
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<RelayTeamDetails>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<RelayTeamDetails> {
+    let name_selector = Sel::new("div.pageTitle");
+    let competition_selector = Sel::new("div#menubottom > a.hidden-xs");
+    let info_row_selector = Sel::new("table#teaminfo > tbody > tr");
+    let members_selector = Sel::new("table#teamleden");
+    let results_selector = Sel::new("table#teamuitslagen");
+    let header_selector = Sel::new("thead > tr > th");
+    let row_selector = Sel::new("tbody > tr");
+    let th_selector = Sel::new("th");
+    let td_selector = Sel::new("td");
+    let a_selector = Sel::new("a");
+    let data_span_selector = Sel::new("span.sortData");
+    let visible_span_selector = Sel::new("span.tipped");
+    let split_selector = Sel::new("span.tussentijd");
+    let re_competition_id = Re::new(REGEX_COMPETITION_ID);
+    let re_participant_id = Re::new(REGEX_PARTICIPANT_ID);
+    let re_club_id = Re::new(REGEX_CLUB_ID);
+    let ctx = ParseCtx::new(MODEL);
+
+    let name = ctx.text(ctx.select_first(html.root_element(), &name_selector)?, "name")?.to_string();
+    let competition_url = ctx.attr(ctx.select_first(html.root_element(), &competition_selector)?, "href")?;
+    let competition_id = ctx.value("competition id", &ctx.captures(&re_competition_id, competition_url)?[1])?;
+
+    let mut team = RelayTeamDetails {
+        competition_id,
+        name,
+        club: None,
+        club_id: None,
+        category: None,
+        event_name: None,
+        members: Vec::new(),
+        results: Vec::new(),
+    };
+
+    for (idx, row) in html.select(&info_row_selector).enumerate() {
+        let ctx = ctx.row(idx);
+        let info = ctx.select_first(row, &th_selector).and_then(|th| Ok((ctx.text(th, "label")?, ctx.select_first(row, &td_selector)?)));
+        let (label, td) = match info {
+            Ok(v) => v,
+            Err(e) => {
+                warnings.skip_row(e, &row.html())?;
+                continue;
+            }
+        };
+
+        match label {
+            "Club" => {
+                team.club = cell_text(td);
+                team.club_id = match td.select(&a_selector).next() {
+                    Some(a) => Some(ctx.value("club id", &ctx.captures(&re_club_id, ctx.attr(a, "href")?)?[1])?),
+                    None => None,
+                };
+            }
+            "Category" => team.category = cell_text(td),
+            "Event" => team.event_name = cell_text(td),
+            _ => (),
+        }
+    }
+
+    let parse_member = |ctx: ParseCtx, columns: &MemberColumns, row: ElementRef| -> crate::Result<RelayMember> {
+        let cells: Vec<ElementRef> = row.select(&td_selector).collect();
+        let cell = |column: Option<usize>| column.and_then(|v| cells.get(v)).copied();
+        let text = |column: Option<usize>| cell(column).and_then(cell_text);
+
+        let name_cell = cell(columns.name).ok_or_else(|| ctx.error(ParseDetail::MissingElement { selector: td_selector.css() }))?;
+        let participant_id = match name_cell.select(&a_selector).next() {
+            Some(a) => Some(ctx.value("participant id", &ctx.captures(&re_participant_id, ctx.attr(a, "href")?)?[1])?),
+            None => None,
+        };
+
+        Ok(RelayMember {
+            leg: text(columns.leg).map(|v| ctx.value("leg", &v)).transpose()?,
+            bib: text(columns.bib),
+            participant_id,
+            name: ctx.text(name_cell, "name")?.replace("  ", " "),
+            category: text(columns.category),
+        })
+    };
+
+    let parse_result = |ctx: ParseCtx, columns: &ResultColumns, row: ElementRef| -> crate::Result<RelayResult> {
+        let cells: Vec<ElementRef> = row.select(&td_selector).collect();
+        let cell = |column: Option<usize>| column.and_then(|v| cells.get(v)).copied();
+        let text = |column: Option<usize>| cell(column).and_then(cell_text);
+
+        let result_cell = cell(columns.result).ok_or_else(|| ctx.error(ParseDetail::MissingElement { selector: td_selector.css() }))?;
+        let performance_text = match result_cell.select(&visible_span_selector).next() {
+            Some(v) => v.text().collect::<String>(),
+            None => result_cell.text().collect::<String>(),
+        }.trim().to_string();
+
+        // same as the results of an event, anything that doesn't start with a digit is a status
+        let data = result_cell.select(&data_span_selector).next();
+        let status = match ResultStatus::parse(&performance_text) {
+            Some(v) => Some(v),
+            None => match data {
+                Some(data) => ResultStatus::from_sentinel(PerformanceKind::Time, ctx.value("performance", ctx.attr(data, "data")?)?),
+                None => None,
+            },
+        };
+        let performance = match (&status, data) {
+            (Some(_), _) => None,
+            (None, data) => match Performance::parse(PerformanceKind::Time, &performance_text) {
+                Some(v) => Some(v),
+                None => match data {
+                    Some(data) => Performance::from_value(PerformanceKind::Time, ctx.value("performance", ctx.attr(data, "data")?)?),
+                    None => return Err(ctx.invalid("performance", &performance_text, "not a time")),
+                },
+            },
+        };
+
+        let mut splits = Vec::new();
+        if let Some(splits_cell) = cell(columns.splits) {
+            for split in splits_cell.select(&split_selector) {
+                let text = ctx.text(split, "split")?;
+                splits.push(Performance::parse(PerformanceKind::Time, text).ok_or_else(|| ctx.invalid("split", text, "not a time"))?);
+            }
+        }
+
+        Ok(RelayResult {
+            round: text(columns.round),
+            place: text(columns.place).map(|v| ctx.value("place", v.trim_end_matches('.'))).transpose()?,
+            performance,
+            performance_text,
+            status,
+            splits,
+        })
+    };
+
+    if let Some(table) = html.select(&members_selector).next() {
+        let columns = MemberColumns::new(&Headers::new(table, &header_selector));
+        for (row_idx, row) in table.select(&row_selector).enumerate() {
+            match parse_member(ctx.row(row_idx), &columns, row) {
+                Ok(v) => team.members.push(v),
+                Err(e) => warnings.skip_row(e, &row.html())?,
+            }
+        }
+    }
+
+    // missing until the team has run
+    if let Some(table) = html.select(&results_selector).next() {
+        let columns = ResultColumns::new(&Headers::new(table, &header_selector));
+        for (row_idx, row) in table.select(&row_selector).enumerate() {
+            match parse_result(ctx.row(row_idx), &columns, row) {
+                Ok(v) => team.results.push(v),
+                Err(e) => warnings.skip_row(e, &row.html())?,
+            }
+        }
+    }
+
+    Ok(team)
+}
+
@@ -0,0 +1,182 @@
+use scraper::{ElementRef, Html};
+use serde::{Deserialize, Serialize};
+use crate::components::table::{cell_text, Headers};
+use crate::error::ParseDetail;
+use crate::parser::{ParseCtx, ParseMode, Parsed, Re, Sel, Warnings};
+use crate::performance::{Performance, PerformanceKind};
+use crate::event::Event;
+
+const MODEL: &str = "start_list";
+
+const REGEX_COMPETITION_ID: &str = r#"wedstrijd/main/([0-9]{1,})/"#;
+const REGEX_PARTICIPANT_ID: &str = r#"atleet/main/([0-9]{1,})/"#;
+
+/// Start list of one event in the timetable, `wedstrijd/startlijst/{id}/{number}/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartList {
+    pub competition_id: u32,
+    pub event_name: String,
+    /// e.g. `Women U20`
+    pub start_group_name: Option<String>,
+    /// e.g. `Heats`
+    pub round: Option<String>,
+    pub heats: Vec<StartListHeat>,
+}
+
+impl StartList {
+    /// From the name in the header
+    pub fn canonical_event(&self) -> Event {
+        Event::parse(&self.event_name)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartListHeat {
+    /// e.g. `Heat 1`, empty if there's only one heat without a name
+    pub name: String,
+    pub entries: Vec<StartListEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StartListEntry {
+    /// Lane for track events, the order of the attempts for field events
+    pub lane: Option<u16>,
+    pub bib: Option<String>,
+    pub participant_id: Option<u32>,
+    pub name: String,
+    pub club: Option<String>,
+    pub category: Option<String>,
+    /// The season or personal best the athlete is seeded on
+    pub seed: Option<Performance>,
+    /// The seed as shown on the site, e.g. `12,51`
+    pub seed_text: Option<String>,
+}
+
+/// Index of every column in a start list table, if the page has it
+struct Columns {
+    lane: Option<usize>,
+    bib: Option<usize>,
+    name: Option<usize>,
+    club: Option<usize>,
+    category: Option<usize>,
+    seed: Option<usize>,
+}
+
+impl Columns {
+    fn new(headers: &Headers) -> Self {
+        Self {
+            lane: headers.find(&["Lane", "Order"]),
+            bib: headers.find(&["Bib"]),
+            name: headers.find(&["Name"]),
+            club: headers.find(&["Club"]),
+            category: headers.find(&["Cat.", "Category"]),
+            seed: headers.find(&["SB", "PB", "Seed"]),
+        }
+    }
+}
+
+/// Expects the DESKTOP site
+pub fn parse(html: Html) -> crate::Result<StartList> {
+    parse_with(html, &mut Warnings::new(ParseMode::Strict))
+}
+
+/// Expects the DESKTOP site
+pub fn parse_lenient(html: Html) -> crate::Result<Parsed<StartList>> {
+    let mut warnings = Warnings::new(ParseMode::Lenient);
+    let data = parse_with(html, &mut warnings)?;
+    Ok(warnings.finish(data))
+}
+
+pub(crate) fn parse_with(html: Html, warnings: &mut Warnings) -> crate::Result<StartList> {
+    let name_selector = Sel::new("div.pageTitle");
+    let competition_selector = Sel::new("div#menubottom > a.hidden-xs");
+    let start_group_selector = Sel::new("div.subtitle > span.startgroep");
+    let round_selector = Sel::new("div.subtitle > span.ronde");
+    let container_selector = Sel::new("div.startlijst");
+    let header_selector = Sel::new("thead > tr > th");
+    let row_selector = Sel::new("tbody > tr");
+    let td_selector = Sel::new("td");
+    let a_selector = Sel::new("a");
+    let data_span_selector = Sel::new("span.sortData");
+    let re_competition_id = Re::new(REGEX_COMPETITION_ID);
+    let re_participant_id = Re::new(REGEX_PARTICIPANT_ID);
+    let ctx = ParseCtx::new(MODEL);
+
+    let event_name = ctx.text(ctx.select_first(html.root_element(), &name_selector)?, "event name")?.to_string();
+    let competition_url = ctx.attr(ctx.select_first(html.root_element(), &competition_selector)?, "href")?;
+    let competition_id = ctx.value("competition id", &ctx.captures(&re_competition_id, competition_url)?[1])?;
+    let start_group_name = html.select(&start_group_selector).next().and_then(cell_text);
+    let round = html.select(&round_selector).next().and_then(cell_text);
+    let kind = PerformanceKind::from_event(&event_name);
+
+    let parse_row = |ctx: ParseCtx, columns: &Columns, row: ElementRef| -> crate::Result<StartListEntry> {
+        let cells: Vec<ElementRef> = row.select(&td_selector).collect();
+        let cell = |column: Option<usize>| column.and_then(|v| cells.get(v)).copied();
+        let text = |column: Option<usize>| cell(column).and_then(cell_text);
+        let missing_td = || ctx.error(ParseDetail::MissingElement { selector: td_selector.css() });
+
+        let name_cell = cell(columns.name).ok_or_else(missing_td)?;
+        let name = ctx.text(name_cell, "name")?.replace("  ", " ");
+        let participant_id = match name_cell.select(&a_selector).next() {
+            Some(a) => Some(ctx.value("participant id", &ctx.captures(&re_participant_id, ctx.attr(a, "href")?)?[1])?),
+            None => None,
+        };
+
+        let seed = match cell(columns.seed).and_then(|v| v.select(&data_span_selector).next()) {
+            Some(data) => {
+                let text = text(columns.seed).unwrap_or_default();
+                match Performance::parse(kind, &text) {
+                    Some(v) => Some(v),
+                    None => Performance::from_value(kind, ctx.value("seed", ctx.attr(data, "data")?)?),
+                }
+            }
+            None => None,
+        };
+
+        Ok(StartListEntry {
+            lane: text(columns.lane).map(|v| ctx.value("lane", &v)).transpose()?,
+            bib: text(columns.bib),
+            participant_id,
+            name,
+            club: text(columns.club),
+            category: text(columns.category),
+            seed,
+            seed_text: text(columns.seed),
+        })
+    };
+
+    let mut heats: Vec<StartListHeat> = Vec::new();
+    // the heats and their tables follow each other in the container, which is missing until the heats are drawn
+    let elements = html.select(&container_selector).flat_map(|v| v.children().filter_map(ElementRef::wrap));
+    for element in elements {
+        match element.value().name() {
+            "h3" => heats.push(StartListHeat {
+                name: element.text().collect::<String>().trim().to_string(),
+                entries: Vec::new(),
+            }),
+            "table" => {
+                if heats.is_empty() {
+                    heats.push(StartListHeat { name: String::new(), entries: Vec::new() });
+                }
+                let heat = heats.last_mut().unwrap();
+                let columns = Columns::new(&Headers::new(element, &header_selector));
+
+                for (row_idx, row) in element.select(&row_selector).enumerate() {
+                    match parse_row(ctx.row(row_idx), &columns, row) {
+                        Ok(v) => heat.entries.push(v),
+                        Err(e) => warnings.skip_row(e, &row.html())?,
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    Ok(StartList {
+        competition_id,
+        event_name,
+        start_group_name,
+        round,
+        heats,
+    })
+}
@@ -0,0 +1,111 @@
+use std::time::Duration;
+use reqwest::StatusCode;
+use url::Url;
+use crate::error::AtnError;
+
+/// A single attempt at a request, as reported to a [`RequestObserver`]
+#[derive(Debug, Clone)]
+pub struct RequestInfo {
+    /// Unique for every attempt, also across clients
+    pub id: usize,
+    pub url: Url,
+    /// Starts at 1, retries count up from there
+    pub attempt: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResponseInfo {
+    pub status: StatusCode,
+    /// Size of the body
+    pub bytes: usize,
+    /// Time from sending the request until the whole body was received
+    pub duration: Duration,
+}
+
+/// Gets notified about every request a client sends, register it with [`crate::AtnClientBuilder::observer`].
+///
+/// The callbacks are called from the task that sends the request, so they shouldn't block.
+pub trait RequestObserver: Send + Sync {
+    fn on_start(&self, _request: &RequestInfo) {}
+
+    /// The site redirected the request, `location` is where it ended up
+    fn on_redirect(&self, _request: &RequestInfo, _location: &Url) {}
+
+    /// The attempt failed and will be sent again after `delay`, as a new request with the next attempt number
+    fn on_retry(&self, _request: &RequestInfo, _delay: Duration, _reason: &str) {}
+
+    fn on_response(&self, _request: &RequestInfo, _response: &ResponseInfo) {}
+
+    /// The request failed without a response, e.g. a timeout
+    fn on_error(&self, _request: &RequestInfo, _error: &AtnError) {}
+
+    /// The page of the (last attempt of the) request was parsed
+    fn on_parse_success(&self, _request: &RequestInfo) {}
+
+    fn on_parse_failure(&self, _request: &RequestInfo, _error: &AtnError) {}
+}
+
+/// Reports every request as a [`tracing`] event, or as a log record if there's no subscriber
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Default)]
+pub struct TracingObserver;
+
+#[cfg(feature = "tracing")]
+impl RequestObserver for TracingObserver {
+    fn on_start(&self, request: &RequestInfo) {
+        tracing::debug!(id = request.id, url = %request.url, attempt = request.attempt, "Request started");
+    }
+
+    fn on_redirect(&self, request: &RequestInfo, location: &Url) {
+        tracing::debug!(id = request.id, url = %request.url, location = %location, "Request redirected");
+    }
+
+    fn on_retry(&self, request: &RequestInfo, delay: Duration, reason: &str) {
+        tracing::warn!(id = request.id, url = %request.url, attempt = request.attempt, delay_ms = delay.as_millis() as u64, reason, "Retrying request");
+    }
+
+    fn on_response(&self, request: &RequestInfo, response: &ResponseInfo) {
+        tracing::info!(
+            id = request.id,
+            url = %request.url,
+            status = response.status.as_u16(),
+            bytes = response.bytes,
+            duration_ms = response.duration.as_millis() as u64,
+            "Request finished"
+        );
+    }
+
+    fn on_error(&self, request: &RequestInfo, error: &AtnError) {
+        tracing::warn!(id = request.id, url = %request.url, error = %error, "Request failed");
+    }
+
+    fn on_parse_success(&self, request: &RequestInfo) {
+        tracing::trace!(id = request.id, url = %request.url, "Page parsed");
+    }
+
+    fn on_parse_failure(&self, request: &RequestInfo, error: &AtnError) {
+        tracing::warn!(id = request.id, url = %request.url, error = %error, "Failed to parse page");
+    }
+}
+
+// std's Instant panics on wasm32 (cloudflare workers), durations are reported as zero there
+pub(crate) struct Stopwatch {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: std::time::Instant,
+}
+
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: std::time::Instant::now(),
+        }
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        return self.start.elapsed();
+        #[cfg(target_arch = "wasm32")]
+        return Duration::ZERO;
+    }
+}
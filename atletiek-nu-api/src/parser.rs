@@ -0,0 +1,224 @@
+use std::fmt::Display;
+use std::ops::Deref;
+use std::str::FromStr;
+use log::warn;
+use regex::{Captures, Regex};
+use scraper::{ElementRef, Selector};
+use serde::{Deserialize, Serialize};
+use crate::error::{AtnError, ParseDetail, Result};
+
+// Longer snippets of html in warnings are cut off
+const MAX_SNIPPET_LEN: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum ParseMode {
+    /// Any row that fails to parse fails the whole page
+    #[default]
+    Strict,
+    /// Rows that fail to parse are skipped and reported as warnings
+    Lenient,
+}
+
+/// Something on the page that couldn't be parsed, but didn't stop the rest of the page from parsing
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParseWarning {
+    pub model: String,
+    pub row: Option<usize>,
+    pub field: String,
+    pub message: String,
+    /// The html of the row, cut off after a few hundred characters
+    pub snippet: String,
+    /// Whether the whole row was left out of the result
+    pub skipped: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Parsed<T> {
+    pub data: T,
+    pub warnings: Vec<ParseWarning>,
+}
+
+/// Selector that remembers its source, so it can be named in errors
+pub(crate) struct Sel {
+    css: &'static str,
+    selector: Selector,
+}
+
+impl Sel {
+    pub fn new(css: &'static str) -> Self {
+        Self {
+            css,
+            // only ever called with constants, so this can't be caused by the page
+            selector: Selector::parse(css).expect("invalid selector"),
+        }
+    }
+
+    pub fn css(&self) -> &'static str {
+        self.css
+    }
+}
+
+impl Deref for Sel {
+    type Target = Selector;
+
+    fn deref(&self) -> &Self::Target {
+        &self.selector
+    }
+}
+
+/// Regex that remembers its source, so it can be named in errors
+pub(crate) struct Re {
+    source: &'static str,
+    regex: Regex,
+}
+
+impl Re {
+    pub fn new(source: &'static str) -> Self {
+        Self {
+            source,
+            regex: Regex::new(source).expect("invalid regex"),
+        }
+    }
+}
+
+impl Deref for Re {
+    type Target = Regex;
+
+    fn deref(&self) -> &Self::Target {
+        &self.regex
+    }
+}
+
+/// Where in the page we are, used to build errors that point at the broken part
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ParseCtx {
+    pub model: &'static str,
+    pub row: Option<usize>,
+}
+
+impl ParseCtx {
+    pub fn new(model: &'static str) -> Self {
+        Self { model, row: None }
+    }
+
+    pub fn row(&self, row: usize) -> Self {
+        Self {
+            model: self.model,
+            row: Some(row),
+        }
+    }
+
+    pub fn error(&self, detail: ParseDetail) -> AtnError {
+        AtnError::Parse {
+            model: self.model,
+            row: self.row,
+            detail,
+        }
+    }
+
+    pub fn select_first<'a>(&self, element: ElementRef<'a>, selector: &Sel) -> Result<ElementRef<'a>> {
+        element
+            .select(selector)
+            .next()
+            .ok_or_else(|| self.error(ParseDetail::MissingElement { selector: selector.css }))
+    }
+
+    pub fn attr<'a>(&self, element: ElementRef<'a>, attribute: &'static str) -> Result<&'a str> {
+        element
+            .value()
+            .attr(attribute)
+            .ok_or_else(|| self.error(ParseDetail::MissingAttribute { attribute }))
+    }
+
+    /// First text node of the element that isn't only whitespace, trimmed
+    pub fn text<'a>(&self, element: ElementRef<'a>, field: &'static str) -> Result<&'a str> {
+        element
+            .text()
+            .map(|v| v.trim())
+            .find(|v| !v.is_empty())
+            .ok_or_else(|| self.error(ParseDetail::MissingText { field }))
+    }
+
+    pub fn captures<'t>(&self, re: &Re, text: &'t str) -> Result<Captures<'t>> {
+        re.captures(text).ok_or_else(|| {
+            self.error(ParseDetail::RegexMismatch {
+                regex: re.source,
+                input: text.to_string(),
+            })
+        })
+    }
+
+    pub fn value<T>(&self, field: &'static str, text: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        text.parse().map_err(|e: T::Err| self.invalid(field, text, e))
+    }
+
+    pub fn warning<M: Display>(&self, field: &str, message: M, snippet: &str, skipped: bool) -> ParseWarning {
+        ParseWarning {
+            model: self.model.to_string(),
+            row: self.row,
+            field: field.to_string(),
+            message: message.to_string(),
+            snippet: snippet_of(snippet),
+            skipped,
+        }
+    }
+
+    pub fn invalid<R: Display>(&self, field: &'static str, value: &str, reason: R) -> AtnError {
+        self.error(ParseDetail::InvalidValue {
+            field,
+            value: value.to_string(),
+            reason: reason.to_string(),
+        })
+    }
+}
+
+/// Collects warnings while parsing a page, and decides if a broken row is fatal
+pub(crate) struct Warnings {
+    mode: ParseMode,
+    list: Vec<ParseWarning>,
+}
+
+impl Warnings {
+    pub fn new(mode: ParseMode) -> Self {
+        Self {
+            mode,
+            list: Vec::new(),
+        }
+    }
+
+    /// Something in the row is off, but the row itself is still usable
+    pub fn warn<M: Display>(&mut self, ctx: &ParseCtx, field: &str, message: M, snippet: &str) {
+        warn!("{}{}: {}: {}", ctx.model, ctx.row.map(|v| format!(" (row {})", v)).unwrap_or_default(), field, message);
+        self.list.push(ctx.warning(field, message, snippet, false));
+    }
+
+    /// The row failed to parse. Errors in strict mode, otherwise the row is skipped with a warning.
+    pub fn skip_row(&mut self, error: AtnError, snippet: &str) -> Result<()> {
+        let (model, row, detail) = match (&self.mode, &error) {
+            (ParseMode::Lenient, AtnError::Parse { model, row, detail }) => (*model, *row, detail),
+            _ => return Err(error),
+        };
+
+        warn!("Skipping row: {}", error);
+        self.list.push(ParseCtx { model, row }.warning(detail.field(), detail, snippet, true));
+        Ok(())
+    }
+
+    pub fn finish<T>(self, data: T) -> Parsed<T> {
+        Parsed {
+            data,
+            warnings: self.list,
+        }
+    }
+}
+
+fn snippet_of(html: &str) -> String {
+    match html.char_indices().nth(MAX_SNIPPET_LEN) {
+        Some((idx, _)) => format!("{}...", &html[..idx]),
+        None => html.to_string(),
+    }
+}
@@ -0,0 +1,322 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::event::Event;
+
+// the site sorts a DNF in a combined event last with this, exactly representable as an f32
+const SENTINEL_DNF: f32 = 9_999_998.0;
+// status codes are short and in capitals, like `DNQ`, anything else is left to the performance parser
+const MAX_STATUS_LEN: usize = 4;
+
+/// What an event is measured in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PerformanceKind {
+    Time,
+    Distance,
+    Height,
+    Points,
+}
+
+impl PerformanceKind {
+    /// From the name or code of an event, e.g. `100 meters`, `Long jump` or `Ver`, see [`Event::performance_kind`]
+    pub fn from_event(event: &str) -> Self {
+        Event::parse(event).performance_kind()
+    }
+
+    /// Only for times, the rest is better when it's higher
+    pub fn lower_is_better(self) -> bool {
+        self == Self::Time
+    }
+}
+
+/// A result as measured, see [`PerformanceKind`]
+///
+/// Compares on which is the better performance, so the best one is the largest for any kind of event.
+/// Performances of a different kind can't be compared.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Performance {
+    Time {
+        duration: Duration,
+        /// Digits after the comma, 1 for hand timing and 2 for electronic timing
+        decimals: u8,
+    },
+    Distance {
+        metres: f32,
+    },
+    Height {
+        metres: f32,
+    },
+    Points {
+        points: u32,
+    },
+}
+
+impl Performance {
+    /// Parses the performance as shown on the site, e.g. `1:02,34`, `11,9h`, `6,12 +1,2m/s` or `3456`
+    ///
+    /// Anything with a `:` is a time, whatever the kind.
+    pub fn parse(kind: PerformanceKind, text: &str) -> Option<Self> {
+        let text = text.split_whitespace().next()?.trim_end_matches('h');
+
+        if kind == PerformanceKind::Time || text.contains(':') {
+            let (whole, fraction) = match text.split_once([',', '.']) {
+                Some((whole, fraction)) => (whole, fraction),
+                None => (text, ""),
+            };
+            if fraction.len() > 9 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+
+            let mut seconds: u64 = 0;
+            for part in whole.split(':') {
+                seconds = seconds.checked_mul(60)?.checked_add(part.parse().ok()?)?;
+            }
+            let nanos = match fraction.is_empty() {
+                true => 0,
+                false => fraction.parse::<u32>().ok()? * 10u32.pow(9 - fraction.len() as u32),
+            };
+
+            return Some(Self::Time {
+                duration: Duration::new(seconds, nanos),
+                decimals: fraction.len() as u8,
+            });
+        }
+
+        match kind {
+            PerformanceKind::Points => Some(Self::Points { points: text.replace('.', "").parse().ok()? }),
+            _ => Self::from_value(kind, text.replace(',', ".").parse().ok()?),
+        }
+    }
+
+    /// From a value in seconds, metres or points, like in the `sortData` of the site
+    ///
+    /// Times get 2 decimals, `None` if the value is negative or not a number.
+    pub fn from_value(kind: PerformanceKind, value: f32) -> Option<Self> {
+        if !value.is_finite() || value < 0.0 {
+            return None;
+        }
+
+        Some(match kind {
+            PerformanceKind::Time => Self::Time {
+                duration: Duration::from_millis((value as f64 * 100.0).round() as u64 * 10),
+                decimals: 2,
+            },
+            PerformanceKind::Distance => Self::Distance { metres: value },
+            PerformanceKind::Height => Self::Height { metres: value },
+            PerformanceKind::Points => Self::Points { points: value.round() as u32 },
+        })
+    }
+
+    pub fn kind(&self) -> PerformanceKind {
+        match self {
+            Self::Time { .. } => PerformanceKind::Time,
+            Self::Distance { .. } => PerformanceKind::Distance,
+            Self::Height { .. } => PerformanceKind::Height,
+            Self::Points { .. } => PerformanceKind::Points,
+        }
+    }
+
+    /// Seconds, metres or points
+    pub fn value(&self) -> f32 {
+        match self {
+            Self::Time { duration, .. } => duration.as_secs_f32(),
+            Self::Distance { metres } | Self::Height { metres } => *metres,
+            Self::Points { points } => *points as f32,
+        }
+    }
+}
+
+impl PartialOrd for Performance {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            // equal times compare on their precision, so this agrees with ==
+            (Self::Time { duration, decimals }, Self::Time { duration: other_duration, decimals: other_decimals }) => {
+                Some(other_duration.cmp(duration).then(decimals.cmp(other_decimals)))
+            }
+            (Self::Distance { metres }, Self::Distance { metres: other }) => metres.partial_cmp(other),
+            (Self::Height { metres }, Self::Height { metres: other }) => metres.partial_cmp(other),
+            (Self::Points { points }, Self::Points { points: other }) => Some(points.cmp(other)),
+            _ => None,
+        }
+    }
+}
+
+/// In the style of the site, `1:02,34`, `6,12` or `3456`
+impl Display for Performance {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Time { duration, decimals } => {
+                let scale = 10u64.pow(*decimals as u32);
+                let units = (duration.as_nanos() as f64 * scale as f64 / 1e9).round() as u64;
+                let seconds = units / scale;
+                let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+                match (hours, minutes) {
+                    (0, 0) => write!(f, "{}", seconds)?,
+                    (0, _) => write!(f, "{}:{:02}", minutes, seconds)?,
+                    _ => write!(f, "{}:{:02}:{:02}", hours, minutes, seconds)?,
+                }
+                match decimals {
+                    0 => Ok(()),
+                    _ => write!(f, ",{:0width$}", units % scale, width = *decimals as usize),
+                }
+            }
+            Self::Distance { metres } | Self::Height { metres } => write!(f, "{}", format!("{:.2}", metres).replace('.', ",")),
+            Self::Points { points } => write!(f, "{}", points),
+        }
+    }
+}
+
+/// What happened instead of a performance
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResultStatus {
+    /// `DNS`
+    DidNotStart,
+    /// `DNF`
+    DidNotFinish,
+    /// `DQ`, with the rule if the site shows it, e.g. `TR16.8`
+    Disqualified {
+        rule: Option<String>,
+    },
+    /// `NM`, no valid mark in a field event
+    NoMark,
+    /// `NH`, no height cleared
+    NoHeight,
+    /// `r`, retired from a field event
+    Retired,
+    /// `-`, a pass in a field series
+    Pass,
+    /// `x`, a foul in a field series
+    Foul,
+    /// Anything else the site shows instead of a performance
+    Other(String),
+}
+
+impl ResultStatus {
+    /// From the text as shown on the site, e.g. `DNS` or `DQ (TR16.8)`, `None` if it's a performance
+    /// or anything else that isn't a status code
+    pub fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() || text.starts_with(|c: char| c.is_ascii_digit()) {
+            return None;
+        }
+
+        let (code, rest) = text.split_once(|c: char| c.is_whitespace() || c == '(').unwrap_or((text, ""));
+        Some(match code.to_uppercase().as_str() {
+            "DNS" => Self::DidNotStart,
+            "DNF" => Self::DidNotFinish,
+            "DQ" | "DSQ" => Self::Disqualified {
+                rule: Some(rest.trim_matches(|c: char| c.is_whitespace() || c == '(' || c == ')').to_string()).filter(|v| !v.is_empty()),
+            },
+            "NM" => Self::NoMark,
+            "NH" => Self::NoHeight,
+            "R" => Self::Retired,
+            "-" => Self::Pass,
+            "X" => Self::Foul,
+            _ if rest.is_empty() && code.len() <= MAX_STATUS_LEN && code.chars().all(|c| c.is_ascii_uppercase()) => Self::Other(text.to_string()),
+            _ => return None,
+        })
+    }
+
+    /// From the sentinel in the `sortData` of the site, for when there's no text to go by
+    ///
+    /// `-1` is a DNS, `-2` a DNF or a foul in a field event, `-3` a DQ and `9999998` a DNF in a combined event.
+    pub fn from_sentinel(kind: PerformanceKind, value: f32) -> Option<Self> {
+        Some(if value == SENTINEL_DNF {
+            Self::DidNotFinish
+        } else if value == -1.0 {
+            Self::DidNotStart
+        } else if value == -2.0 && kind == PerformanceKind::Time {
+            Self::DidNotFinish
+        } else if value == -2.0 {
+            Self::Foul
+        } else if value == -3.0 {
+            Self::Disqualified { rule: None }
+        } else if value < 0.0 {
+            Self::Other(value.to_string())
+        } else {
+            return None;
+        })
+    }
+}
+
+/// The code the site uses, e.g. `DNF` or `DQ (TR16.8)`
+impl Display for ResultStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DidNotStart => write!(f, "DNS"),
+            Self::DidNotFinish => write!(f, "DNF"),
+            Self::Disqualified { rule: Some(rule) } => write!(f, "DQ ({})", rule),
+            Self::Disqualified { rule: None } => write!(f, "DQ"),
+            Self::NoMark => write!(f, "NM"),
+            Self::NoHeight => write!(f, "NH"),
+            Self::Retired => write!(f, "r"),
+            Self::Pass => write!(f, "-"),
+            Self::Foul => write!(f, "x"),
+            Self::Other(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+#[test]
+fn test_performance_kind() {
+    assert_eq!(PerformanceKind::from_event("100 meters"), PerformanceKind::Time);
+    assert_eq!(PerformanceKind::from_event("60mH"), PerformanceKind::Time);
+    assert_eq!(PerformanceKind::from_event("Ver"), PerformanceKind::Distance);
+    assert_eq!(PerformanceKind::from_event("Shot put"), PerformanceKind::Distance);
+    assert_eq!(PerformanceKind::from_event("Hoog"), PerformanceKind::Height);
+    assert_eq!(PerformanceKind::from_event("Zevenkamp"), PerformanceKind::Points);
+}
+
+#[test]
+fn test_performance_parse_and_display() {
+    let time = Performance::parse(PerformanceKind::Time, "1:02,34").unwrap();
+    assert_eq!(time, Performance::Time { duration: Duration::from_millis(62340), decimals: 2 });
+    assert_eq!(time.to_string(), "1:02,34");
+    assert_eq!(Performance::parse(PerformanceKind::Time, "11,9h").unwrap().to_string(), "11,9");
+    assert_eq!(Performance::parse(PerformanceKind::Time, "2:31:07").unwrap().to_string(), "2:31:07");
+    assert_eq!(Performance::parse(PerformanceKind::Distance, "6,12 +1,2m/s"), Some(Performance::Distance { metres: 6.12 }));
+    assert_eq!(Performance::parse(PerformanceKind::Points, "3456"), Some(Performance::Points { points: 3456 }));
+    assert_eq!(Performance::parse(PerformanceKind::Distance, "DNS"), None);
+    assert_eq!(Performance::from_value(PerformanceKind::Time, 11.92).unwrap().to_string(), "11,92");
+    assert_eq!(Performance::from_value(PerformanceKind::Height, 1.5).unwrap().to_string(), "1,50");
+    assert_eq!(Performance::from_value(PerformanceKind::Time, -1.0), None);
+}
+
+#[test]
+fn test_performance_ordering() {
+    let fast = Performance::from_value(PerformanceKind::Time, 11.92).unwrap();
+    let slow = Performance::from_value(PerformanceKind::Time, 12.08).unwrap();
+    assert!(fast > slow);
+
+    let far = Performance::Distance { metres: 6.12 };
+    assert!(far > Performance::Distance { metres: 5.98 });
+    assert_eq!(far.partial_cmp(&fast), None);
+}
+
+#[test]
+fn test_result_status() {
+    assert_eq!(ResultStatus::parse("DNS"), Some(ResultStatus::DidNotStart));
+    assert_eq!(ResultStatus::parse("DQ (TR16.8)"), Some(ResultStatus::Disqualified { rule: Some("TR16.8".to_string()) }));
+    assert_eq!(ResultStatus::parse("DQ"), Some(ResultStatus::Disqualified { rule: None }));
+    assert_eq!(ResultStatus::parse("x"), Some(ResultStatus::Foul));
+    assert_eq!(ResultStatus::parse("-"), Some(ResultStatus::Pass));
+    assert_eq!(ResultStatus::parse("r"), Some(ResultStatus::Retired));
+    assert_eq!(ResultStatus::parse("12,08"), None);
+    assert_eq!(ResultStatus::parse("DNQ"), Some(ResultStatus::Other("DNQ".to_string())));
+    assert_eq!(ResultStatus::parse("PB 12,08"), None);
+    assert_eq!(ResultStatus::parse(",95"), None);
+    assert_eq!(ResultStatus::parse("-0,3"), None);
+    assert_eq!(ResultStatus::parse("<b>12,08</b>"), None);
+    assert_eq!(ResultStatus::parse(""), None);
+    assert_eq!(ResultStatus::from_sentinel(PerformanceKind::Time, -2.0), Some(ResultStatus::DidNotFinish));
+    assert_eq!(ResultStatus::from_sentinel(PerformanceKind::Distance, -2.0), Some(ResultStatus::Foul));
+    assert_eq!(ResultStatus::from_sentinel(PerformanceKind::Time, 9999998.0), Some(ResultStatus::DidNotFinish));
+    assert_eq!(ResultStatus::from_sentinel(PerformanceKind::Time, 11.92), None);
+    // a 50 km race walk or a slow marathon, not a DNF
+    assert_eq!(ResultStatus::from_sentinel(PerformanceKind::Time, 13000.0), None);
+    assert_eq!(ResultStatus::from_sentinel(PerformanceKind::Points, 9_999_999.0), None);
+    assert_eq!(ResultStatus::from_sentinel(PerformanceKind::Time, -1.5), Some(ResultStatus::Other("-1.5".to_string())));
+    assert_eq!(ResultStatus::parse("DQ (TR24.19)").unwrap().to_string(), "DQ (TR24.19)");
+}
@@ -0,0 +1,191 @@
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use crate::error::AtnError;
+
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// When and how long to wait before sending a failed request again.
+///
+/// Only network errors and the statuses that usually go away by themselves (408, 429, 500, 502, 503 and 504)
+/// are retried. The delay doubles every attempt, starting at the base delay, unless the site sent a `Retry-After`.
+/// On wasm32 there's no timer to wait with, so nothing is retried there whatever the policy says.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Only sends every request once
+    pub fn none() -> Self {
+        Self::default().max_attempts(1)
+    }
+
+    /// Total number of attempts including the first one, at least 1
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Upper bound for the delay between two attempts. A `Retry-After` longer than this is not waited for,
+    /// the error is returned instead.
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Randomizes the delay between half and the full backoff, so clients don't retry in lockstep
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn get_max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// How long to wait before the next attempt after `attempt` (starting at 1) failed,
+    /// or `None` if the request shouldn't be sent again
+    pub(crate) fn retry_delay(&self, attempt: u32, failure: &Failure) -> Option<Duration> {
+        // retrying without the delay would hammer the site right when it asks us to back off
+        if cfg!(target_arch = "wasm32") || attempt >= self.max_attempts {
+            return None;
+        }
+
+        let retry_after = match failure {
+            Failure::Error(e) => match e {
+                AtnError::Network(_) => None,
+                _ => return None,
+            },
+            Failure::Status { status, headers } => {
+                if !is_retryable(*status) {
+                    return None;
+                }
+
+                match *status {
+                    StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE => parse_retry_after(headers),
+                    _ => None,
+                }
+            },
+        };
+
+        if let Some(retry_after) = retry_after {
+            if retry_after > self.max_delay {
+                return None;
+            }
+
+            return Some(retry_after);
+        }
+
+        let backoff = self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_delay);
+
+        if self.jitter {
+            Some(backoff / 2 + backoff.mul_f64(rand::thread_rng().gen_range(0.0..0.5)))
+        } else {
+            Some(backoff)
+        }
+    }
+}
+
+/// Why an attempt failed
+pub(crate) enum Failure<'a> {
+    Error(&'a AtnError),
+    Status { status: StatusCode, headers: &'a HeaderMap },
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+// Either a number of seconds or an HTTP date
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+// there's no timer on cloudflare workers, never called as nothing is retried there
+#[cfg(target_arch = "wasm32")]
+pub(crate) async fn sleep(_duration: Duration) {}
+
+#[test]
+fn test_retry_delay() {
+    let policy = RetryPolicy::default().jitter(false);
+    let headers = HeaderMap::new();
+    let status = |status| Failure::Status { status, headers: &headers };
+
+    assert_eq!(policy.retry_delay(1, &status(StatusCode::BAD_GATEWAY)), Some(DEFAULT_BASE_DELAY));
+    assert_eq!(policy.retry_delay(2, &status(StatusCode::BAD_GATEWAY)), Some(DEFAULT_BASE_DELAY * 2));
+    assert_eq!(policy.retry_delay(3, &status(StatusCode::BAD_GATEWAY)), None);
+    assert_eq!(policy.retry_delay(1, &status(StatusCode::NOT_FOUND)), None);
+    assert_eq!(policy.retry_delay(1, &Failure::Error(&AtnError::NotFound { url: String::new() })), None);
+
+    let policy = policy.max_attempts(10);
+    assert_eq!(policy.retry_delay(9, &status(StatusCode::BAD_GATEWAY)), Some(DEFAULT_MAX_DELAY));
+
+    let mut headers = HeaderMap::new();
+    headers.insert(RETRY_AFTER, "3".parse().unwrap());
+    assert_eq!(policy.retry_delay(1, &Failure::Status { status: StatusCode::TOO_MANY_REQUESTS, headers: &headers }), Some(Duration::from_secs(3)));
+    // only 429 and 503 come with a meaningful Retry-After
+    assert_eq!(policy.retry_delay(1, &Failure::Status { status: StatusCode::BAD_GATEWAY, headers: &headers }), Some(DEFAULT_BASE_DELAY));
+
+    headers.insert(RETRY_AFTER, "3600".parse().unwrap());
+    assert_eq!(policy.retry_delay(1, &Failure::Status { status: StatusCode::SERVICE_UNAVAILABLE, headers: &headers }), None);
+
+    headers.insert(RETRY_AFTER, "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap());
+    assert_eq!(policy.retry_delay(1, &Failure::Status { status: StatusCode::TOO_MANY_REQUESTS, headers: &headers }), Some(Duration::ZERO));
+}
+
+#[test]
+fn test_retry_jitter() {
+    let policy = RetryPolicy::default();
+    let headers = HeaderMap::new();
+
+    for _ in 0..100 {
+        let delay = policy.retry_delay(2, &Failure::Status { status: StatusCode::GATEWAY_TIMEOUT, headers: &headers }).unwrap();
+        assert!(delay >= DEFAULT_BASE_DELAY && delay <= DEFAULT_BASE_DELAY * 2);
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::models::competitions_list_web::{CompetitionKind, CompetitionsWebList};
+
+/// Length of the windows [`crate::AtnClient::search_competitions_range`] splits a period into.
+///
+/// The feeder drops competitions from the list for long periods without saying so: there is
+/// no total, no paging and no marker on a cut off list, so truncation can't be detected from a
+/// response. This isn't a limit the site documents, two weeks is a conservative choice that
+/// keeps even the busiest part of the outdoor season well within one response.
+pub const SEARCH_WINDOW_DAYS: u64 = 14;
+
+/// Country to search competitions in, sent to the site as its ISO-2 code
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Country {
+    /// Competitions in every country
+    All,
+    Netherlands,
+    Belgium,
+    Germany,
+    Luxembourg,
+    France,
+    UnitedKingdom,
+    Spain,
+    Portugal,
+    Suriname,
+    Curacao,
+    Aruba,
+    /// Any other ISO-2 code, in upper case
+    Other(String),
+}
+
+const SUPPORTED_COUNTRIES: &[Country] = &[
+    Country::All,
+    Country::Netherlands,
+    Country::Belgium,
+    Country::Germany,
+    Country::Luxembourg,
+    Country::France,
+    Country::UnitedKingdom,
+    Country::Spain,
+    Country::Portugal,
+    Country::Suriname,
+    Country::Curacao,
+    Country::Aruba,
+];
+
+impl Country {
+    /// The countries that have competitions listed on athletics.app, starting with [`Country::All`]
+    pub fn supported() -> &'static [Country] {
+        SUPPORTED_COUNTRIES
+    }
+
+    /// Parses an ISO-2 code or `all`, case insensitive
+    pub fn from_code(code: &str) -> Self {
+        let code = code.trim().to_uppercase();
+        SUPPORTED_COUNTRIES
+            .iter()
+            .find(|v| v.code().eq_ignore_ascii_case(&code))
+            .cloned()
+            .unwrap_or(Country::Other(code))
+    }
+
+    /// ISO-2 code, or `all`
+    pub fn code(&self) -> &str {
+        match self {
+            Self::All => "all",
+            Self::Netherlands => "NL",
+            Self::Belgium => "BE",
+            Self::Germany => "DE",
+            Self::Luxembourg => "LU",
+            Self::France => "FR",
+            Self::UnitedKingdom => "GB",
+            Self::Spain => "ES",
+            Self::Portugal => "PT",
+            Self::Suriname => "SR",
+            Self::Curacao => "CW",
+            Self::Aruba => "AW",
+            Self::Other(code) => code,
+        }
+    }
+
+    /// English name, the code for [`Country::Other`]
+    pub fn name(&self) -> &str {
+        match self {
+            Self::All => "All countries",
+            Self::Netherlands => "Netherlands",
+            Self::Belgium => "Belgium",
+            Self::Germany => "Germany",
+            Self::Luxembourg => "Luxembourg",
+            Self::France => "France",
+            Self::UnitedKingdom => "United Kingdom",
+            Self::Spain => "Spain",
+            Self::Portugal => "Portugal",
+            Self::Suriname => "Suriname",
+            Self::Curacao => "Curaçao",
+            Self::Aruba => "Aruba",
+            Self::Other(code) => code,
+        }
+    }
+}
+
+impl Display for Country {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Country {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from_code(s))
+    }
+}
+
+impl From<&str> for Country {
+    fn from(value: &str) -> Self {
+        Self::from_code(value)
+    }
+}
+
+impl From<String> for Country {
+    fn from(value: String) -> Self {
+        Self::from_code(&value)
+    }
+}
+
+impl From<Country> for String {
+    fn from(value: Country) -> Self {
+        value.code().to_string()
+    }
+}
+
+/// Search for competitions in a time period, see [`crate::AtnClient::search_competitions_with`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CompetitionSearch {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Text to search for in the name or location, empty for all competitions
+    pub query: String,
+    /// `None` uses the default country of the client
+    pub country: Option<Country>,
+    /// Every kind is a separate request to the site, empty for all kinds
+    pub kinds: Vec<CompetitionKind>,
+    /// Only competitions that do (or don't) have results yet. The feeder has no parameter for this,
+    /// so it's filtered on the rows it returns.
+    pub results_available: Option<bool>,
+    /// Only competitions that are (or aren't) for club members only, filtered like `results_available`
+    pub club_members_only: Option<bool>,
+}
+
+impl CompetitionSearch {
+    pub fn new(start: NaiveDate, end: NaiveDate) -> Self {
+        Self {
+            start,
+            end,
+            query: String::new(),
+            country: None,
+            kinds: vec![CompetitionKind::Indoor, CompetitionKind::Outdoor],
+            results_available: None,
+            club_members_only: None,
+        }
+    }
+
+    pub fn query<T: ToString>(mut self, query: T) -> Self {
+        self.query = query.to_string();
+        self
+    }
+
+    pub fn country<C: Into<Country>>(mut self, country: C) -> Self {
+        self.country = Some(country.into());
+        self
+    }
+
+    /// Replaces the default of indoor and outdoor competitions
+    pub fn kinds<I: IntoIterator<Item = CompetitionKind>>(mut self, kinds: I) -> Self {
+        self.kinds = kinds.into_iter().collect();
+        self
+    }
+
+    pub fn results_available(mut self, results_available: bool) -> Self {
+        self.results_available = Some(results_available);
+        self
+    }
+
+    pub fn club_members_only(mut self, club_members_only: bool) -> Self {
+        self.club_members_only = Some(club_members_only);
+        self
+    }
+
+    pub(crate) fn kinds_to_fetch(&self) -> Vec<CompetitionKind> {
+        match self.kinds.is_empty() {
+            true => CompetitionKind::ALL.to_vec(),
+            false => self.kinds.clone(),
+        }
+    }
+
+    /// Splits the period into consecutive searches of at most `days` days, both ends are inclusive
+    pub(crate) fn windows(&self, days: u64) -> Vec<CompetitionSearch> {
+        let mut windows = Vec::new();
+        let mut start = self.start;
+        while start <= self.end {
+            let end = start
+                .checked_add_days(Days::new(days.max(1) - 1))
+                .map_or(self.end, |v| v.min(self.end));
+            windows.push(CompetitionSearch {
+                start,
+                end,
+                ..self.clone()
+            });
+
+            match end.succ_opt() {
+                Some(v) => start = v,
+                None => break,
+            }
+        }
+
+        windows
+    }
+
+    /// Merges the lists of the different kinds by date, keeping a competition only under the first kind it was found for,
+    /// and applies the filters the site doesn't know about
+    pub(crate) fn apply(&self, mut list: CompetitionsWebList) -> CompetitionsWebList {
+        let mut seen = HashSet::new();
+        list.retain(|v| {
+            seen.insert(v.competition_id)
+                && self.results_available.is_none_or(|f| v.results_availible == f)
+                && self.club_members_only.is_none_or(|f| v.club_members_only == f)
+        });
+
+        // stable, so the order of the site is kept within a day
+        list.sort_by_key(|v| v.date);
+        list
+    }
+}
+
+#[test]
+fn test_country_codes() {
+    assert_eq!(Country::from_code("be"), Country::Belgium);
+    assert_eq!(Country::from_code("ALL"), Country::All);
+    assert_eq!(Country::from_code("ch"), Country::Other("CH".to_string()));
+    assert_eq!(Country::All.code(), "all");
+
+    for country in Country::supported() {
+        assert_eq!(&Country::from_code(country.code()), country);
+    }
+}
+
+#[test]
+fn test_search_windows() {
+    let search = CompetitionSearch::new(
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+    ).query("spike");
+    let windows = search.windows(SEARCH_WINDOW_DAYS);
+
+    assert_eq!(windows.len(), 27);
+    assert_eq!(windows[0].end, NaiveDate::from_ymd_opt(2024, 1, 14).unwrap());
+    assert_eq!(windows[26].start, NaiveDate::from_ymd_opt(2024, 12, 30).unwrap());
+    assert_eq!(windows[26].end, search.end);
+    assert!(windows.windows(2).all(|v| v[0].end.succ_opt() == Some(v[1].start)));
+    assert!(windows.iter().all(|v| v.query == "spike"));
+
+    let day = CompetitionSearch::new(search.start, search.start);
+    assert_eq!(day.windows(SEARCH_WINDOW_DAYS).len(), 1);
+    assert!(CompetitionSearch::new(search.end, search.start).windows(SEARCH_WINDOW_DAYS).is_empty());
+}
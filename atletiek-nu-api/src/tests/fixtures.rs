@@ -0,0 +1,532 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::NaiveDate;
+use regex::Regex;
+use scraper::Html;
+use serde::Serialize;
+use crate::models;
+use crate::event::Event;
+use crate::performance::{Performance, ResultStatus};
+use crate::models::athlete_event_result::{AthleteEventResults, AttemptOutcome, EventResultItem, ResultRound};
+use crate::models::athlete_profile::{AthleteProfile, EventAttribute};
+use crate::models::competition_details::Fee;
+use crate::models::competitions_list_web::CompetitionKind;
+use crate::models::registrations_list_web::{EventStatus, RegistrationsWebList};
+use crate::transport::{recording_file_name, RecordTransport, ReqwestTransport, Transport};
+
+const FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/test-data");
+// Set to rewrite the snapshots from the current parser output instead of comparing against them
+const ENV_UPDATE_SNAPSHOTS: &str = "ATN_UPDATE_SNAPSHOTS";
+const LIVE_BASE_URL: &str = "https://www.athletics.app";
+// Fixtures written by hand instead of recorded from the site
+const SYNTHETIC_PREFIX: &str = "synthetic_";
+
+type Snapshot = fn(&str) -> crate::Result<String>;
+
+fn fixture(name: &str) -> String {
+    let path = PathBuf::from(FIXTURE_DIR).join(format!("{}.html", name));
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("Failed to read fixture {}: {}", path.display(), e))
+}
+
+fn to_json<T: Serialize>(value: T) -> crate::Result<String> {
+    Ok(serde_json::to_string_pretty(&value).unwrap())
+}
+
+fn registrations(name: &str) -> RegistrationsWebList {
+    models::registrations_list_web::parse(Html::parse_document(&fixture(name))).unwrap()
+}
+
+fn event_results(html: &str) -> crate::Result<AthleteEventResults> {
+    models::athlete_event_result::parse(Html::parse_document(html))
+}
+
+fn profile(name: &str) -> AthleteProfile {
+    models::athlete_profile::parse(Html::parse_document(&fixture(name))).unwrap()
+}
+
+fn snapshots() -> Vec<(&'static str, Snapshot)> {
+    vec![
+        ("athlete_search", |s| to_json(models::athlete_list::parse(Html::parse_fragment(s))?)),
+        ("competitions_search", |s| to_json(models::competitions_list::parse(Html::parse_fragment(s))?)),
+        ("registrations_app", |s| to_json(models::registrations_list::parse(Html::parse_fragment(s))?)),
+        ("registrations_relay_teams", |s| to_json(models::registrations_list_web::parse(Html::parse_document(s))?)),
+        ("registrations_event_status", |s| to_json(models::registrations_list_web::parse(Html::parse_document(s))?)),
+        ("registrations_multiple_events", |s| to_json(models::registrations_list_web::parse(Html::parse_document(s))?)),
+        ("feeder_short", |s| to_json(models::competitions_list_web::parse(Html::parse_document(s))?)),
+        ("synthetic_feeder_long", |s| to_json(models::competitions_list_web::parse(Html::parse_document(s))?)),
+        ("synthetic_competition_main", |s| to_json(models::competition_details::parse(Html::parse_document(s))?)),
+        ("synthetic_event_results_100m", |s| to_json(models::event_results::parse(Html::parse_document(s))?)),
+        ("synthetic_competition_timetable", |s| to_json(models::competition_timetable::parse(Html::parse_document(s))?)),
+        ("synthetic_club_main", |s| to_json(models::club::parse(Html::parse_document(s))?)),
+        ("synthetic_relay_team", |s| to_json(models::relay_team::parse(Html::parse_document(s))?)),
+        ("synthetic_start_list_100m", |s| to_json(models::start_list::parse(Html::parse_document(s))?)),
+        ("profile_implements", |s| to_json(models::athlete_profile::parse(Html::parse_document(s))?)),
+        ("event_result_combined_dnf", |s| to_json(event_results(s)?)),
+        ("event_result_field_dnf", |s| to_json(event_results(s)?)),
+        ("event_result_multiday", |s| to_json(event_results(s)?)),
+        ("synthetic_event_result_series", |s| to_json(event_results(s)?)),
+        ("synthetic_event_result_rounds", |s| to_json(event_results(s)?)),
+    ]
+}
+
+#[test]
+fn test_fixture_snapshots() {
+    let update = std::env::var_os(ENV_UPDATE_SNAPSHOTS).is_some();
+    let mut mismatches = Vec::new();
+
+    for (name, snapshot) in snapshots() {
+        let json = snapshot(&fixture(name)).unwrap_or_else(|e| panic!("Failed to parse fixture {}: {}", name, e));
+        let path = PathBuf::from(FIXTURE_DIR).join(format!("{}.json", name));
+
+        if update {
+            std::fs::write(&path, format!("{}\n", json)).unwrap();
+            continue;
+        }
+
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read snapshot {} (set {} to create it): {}", path.display(), ENV_UPDATE_SNAPSHOTS, e));
+        if expected.trim_end() != json {
+            mismatches.push(name);
+        }
+    }
+
+    assert!(mismatches.is_empty(), "Parser output differs from the snapshots for {:?}, set {} to update them", mismatches, ENV_UPDATE_SNAPSHOTS);
+}
+
+/// The paths in `routes.txt` with the fixture they're served from
+fn routes() -> Vec<(String, String)> {
+    let routes = std::fs::read_to_string(PathBuf::from(FIXTURE_DIR).join("routes.txt")).unwrap();
+    routes.lines()
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty() && !v.starts_with('#'))
+        .filter_map(|v| v.split_once(char::is_whitespace))
+        .map(|(path, file)| (path.to_string(), file.trim().to_string()))
+        .collect()
+}
+
+#[test]
+fn test_fixtures_recorded_or_synthetic() {
+    // without a route `record_fixtures` can't refresh a page, so it can't show the site has changed
+    let routed: HashSet<_> = routes().into_iter().map(|v| v.1).collect();
+    for (name, _) in snapshots() {
+        assert!(routed.contains(&format!("{}.html", name)) || name.starts_with(SYNTHETIC_PREFIX), "Fixture {} has no route and isn't marked synthetic", name);
+    }
+}
+
+/// Records the pages in `routes.txt` from the live site over the fixtures they're served from, the first
+/// path of a file wins. A synthetic fixture is recorded next to it without the prefix, switch the route and
+/// the tests over to the recording and remove the synthetic page. Rerun the tests with [`ENV_UPDATE_SNAPSHOTS`]
+/// afterwards and check the tests that assert on a fixture still hold.
+#[tokio::test]
+#[ignore = "hits the live site and overwrites the fixtures"]
+async fn record_fixtures() {
+    let dir = std::env::temp_dir().join("atn_record_fixtures");
+    let transport = RecordTransport::new(Arc::new(ReqwestTransport::new(reqwest::Client::new())), &dir);
+    let mut recorded = HashSet::new();
+
+    for (path, file) in routes() {
+        if !recorded.insert(file.clone()) {
+            continue;
+        }
+
+        let url = format!("{}{}", LIVE_BASE_URL, path);
+        let res = transport.get(&url).await.unwrap();
+        assert!(res.status.is_success(), "Got {} for {}", res.status, url);
+        let target = file.strip_prefix(SYNTHETIC_PREFIX).unwrap_or(&file);
+        std::fs::copy(dir.join(recording_file_name(&url)), PathBuf::from(FIXTURE_DIR).join(target)).unwrap();
+    }
+}
+
+#[test]
+fn test_results_combined_event_with_dnf() {
+    let results = event_results(&fixture("event_result_combined_dnf")).unwrap();
+
+    assert_eq!(results.name, "Iris van Leeuwen");
+    assert_eq!(results.competition_id, 38917);
+    assert_eq!(results.get_total_points(), Some(1436));
+    assert_eq!(results.results.len(), 5);
+
+    let result = results.results.iter().find(|v| v.event_name == "800m").unwrap();
+    assert_eq!(result.items.len(), 2);
+    assert!(result.items.contains(&EventResultItem::Points { amount: 0 }));
+    assert!(result.items.contains(&EventResultItem::Measurement {
+        wind_speed: None,
+        result: None,
+        status: Some(ResultStatus::DidNotFinish),
+        attempt: None,
+        heights: vec![],
+    }));
+}
+
+#[test]
+fn test_results_field_event_dnf() {
+    let results = event_results(&fixture("event_result_field_dnf")).unwrap();
+
+    assert_eq!(results.results.len(), 3);
+    let mut expected_foul_counts: HashMap<&str, usize> = HashMap::new();
+    expected_foul_counts.insert("Ver", 2);
+    expected_foul_counts.insert("Kogel", 0);
+    expected_foul_counts.insert("Discus", 4);
+
+    for result in results.results {
+        let statuses: Vec<_> = result.items.iter().filter_map(|v| match v {
+            EventResultItem::Measurement { status, .. } => status.clone(),
+            _ => None,
+        }).collect();
+
+        // shown as `x`, the old check on the sortData took these for DNFs
+        assert!(statuses.iter().all(|v| *v == ResultStatus::Foul));
+        assert_eq!(statuses.len(), expected_foul_counts[result.event_name.as_str()]);
+    }
+}
+
+#[test]
+fn test_results_multiday_timetable() {
+    let results = event_results(&fixture("event_result_multiday")).unwrap();
+
+    // the day header rows are not events
+    assert_eq!(results.timetable.len(), 3);
+    assert_eq!(results.timetable[2].event_short, "200m");
+    assert_eq!(results.timetable[2].time.date_naive(), NaiveDate::from_ymd_opt(2024, 9, 15).unwrap());
+    assert_eq!(results.participated_in.len(), 2);
+    assert_eq!(results.participated_in[1].location.country, "Belgium");
+}
+
+#[test]
+fn test_results_series_heights_and_rounds() {
+    let results = event_results(&fixture("synthetic_event_result_series")).unwrap();
+    assert_eq!(results.results.len(), 4);
+
+    let rounds: Vec<_> = results.results.iter().filter_map(|v| v.round.clone()).collect();
+    assert_eq!(rounds, vec![
+        ResultRound { name: "Heats".to_string(), heat: Some(2), lane: Some(4) },
+        ResultRound { name: "Final".to_string(), heat: None, lane: Some(5) },
+    ]);
+    let heats = results.results.iter().find(|v| v.round.as_ref().is_some_and(|v| v.name == "Heats")).unwrap();
+    assert_eq!(heats.best(), Some(Performance::Time { duration: Duration::from_millis(12050), decimals: 2 }));
+
+    let long_jump = results.results.iter().find(|v| v.event_name == "Ver").unwrap();
+    let series: Vec<_> = long_jump.items.iter().filter_map(|v| match v {
+        EventResultItem::Measurement { attempt, status, .. } => Some((attempt.unwrap(), status.clone())),
+        _ => None,
+    }).collect();
+    assert_eq!(series.iter().map(|v| v.0).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(series[1].1, Some(ResultStatus::Foul));
+    assert_eq!(series[3].1, Some(ResultStatus::Pass));
+    assert_eq!(long_jump.best(), Some(Performance::Distance { metres: 5.63 }));
+
+    let high_jump = results.results.iter().find(|v| v.event_name == "Hoog").unwrap();
+    match &high_jump.items[0] {
+        EventResultItem::Measurement { heights, attempt, .. } => {
+            assert_eq!(*attempt, None);
+            assert_eq!(heights.len(), 5);
+            assert_eq!(heights[1].attempts, vec![AttemptOutcome::Passed]);
+            assert_eq!(heights[3].height, Performance::Height { metres: 1.65 });
+            assert_eq!(heights[3].attempts, vec![AttemptOutcome::Failed, AttemptOutcome::Failed, AttemptOutcome::Cleared]);
+        },
+        v => panic!("Expected a measurement, got {:?}", v),
+    }
+}
+
+#[test]
+fn test_results_page_order_and_timetable() {
+    let results = event_results(&fixture("synthetic_event_result_series")).unwrap();
+
+    let events: Vec<_> = results.results.iter().map(|v| v.event_name.as_str()).collect();
+    assert_eq!(events, vec!["100m", "100m", "Ver", "Hoog"]);
+
+    // the heats and the final are both in the timetable, in that order
+    let times: Vec<_> = results.results.iter().map(|v| v.timetable_event.as_ref().unwrap().time.format("%H:%M").to_string()).collect();
+    assert_eq!(times, vec!["08:00", "14:00", "12:00", "09:30"]);
+    assert_eq!(results.results[2].timetable_event.as_ref().unwrap().event_short, "LJ");
+
+    let results = event_results(&fixture("event_result_multiday")).unwrap();
+    assert_eq!(results.results[1].timetable_event.as_ref().unwrap().time.date_naive(), NaiveDate::from_ymd_opt(2024, 9, 15).unwrap());
+}
+
+#[test]
+fn test_results_timetable_by_round() {
+    let results = event_results(&fixture("synthetic_event_result_rounds")).unwrap();
+
+    // the final is listed first and the semi-final isn't in the timetable
+    let start_groups: Vec<_> = results.results.iter().map(|v| v.timetable_event.as_ref().map(|v| v.start_group_name.as_str())).collect();
+    assert_eq!(start_groups, vec![Some("Girls U16 - Final"), None, Some("Girls U16 - Heats"), Some("Girls U16")]);
+
+    // not in the catalogue, so on the name
+    assert_eq!(results.results[3].timetable_event.as_ref().unwrap().event_short, "Vortex");
+}
+
+#[test]
+fn test_relay_teams() {
+    for i in registrations("registrations_relay_teams") {
+        match i.bib_number {
+            Some(276) | Some(336) => assert_eq!(i.relay_teams.len(), 1),
+            Some(353) => assert_eq!(i.relay_teams.len(), 4),
+            Some(360) => assert!(i.relay_teams.is_empty()),
+            _ => (),
+        }
+    }
+}
+
+#[test]
+fn test_event_status() {
+    let registrations = registrations("registrations_event_status");
+    assert_eq!(registrations.len(), 4);
+
+    for i in registrations {
+        assert!(!i.name.is_empty());
+
+        for (_, status) in &i.events {
+            assert!(status == &EventStatus::CheckedIn || status == &EventStatus::Cancelled || status == &EventStatus::Rejected);
+        }
+
+        match i.bib_number {
+            Some(44) => {
+                assert_eq!(i.events, vec![("400m".to_string(), EventStatus::CheckedIn), ("400m_f".to_string(), EventStatus::CheckedIn)]);
+                assert_eq!(i.team_name.as_deref(), Some("Team Zuid"));
+            },
+            Some(45) => {
+                assert!(i.events.contains(&("60m".to_string(), EventStatus::Rejected)));
+                assert!(i.events.contains(&("60mH".to_string(), EventStatus::CheckedIn)));
+                assert!(i.events.contains(&("SP".to_string(), EventStatus::CheckedIn)));
+            },
+            Some(46) => assert!(i.out_of_competition),
+            _ => (),
+        }
+    }
+}
+
+#[test]
+fn test_multiple_event_registrations() {
+    let re = Regex::new("\\+[0-9] onderdelen").unwrap();
+
+    for i in registrations("registrations_multiple_events") {
+        assert!(!i.events.is_empty());
+        for event in i.events {
+            if re.is_match(&event.0) {
+                panic!("Test failed on participant {}, event '{:?}'", i.participant_id, event);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_profile_implements() {
+    let profile = profile("profile_implements");
+
+    assert_eq!(profile.name, "Marith Siekman");
+    assert_eq!(profile.personal_bests.len(), 6);
+
+    for i in profile.personal_bests {
+        match (i.event.as_str(), i.wind_speed.is_some()) {
+            ("60 meters", _) => {
+                assert_eq!(i.performance, Performance::Time { duration: Duration::from_millis(9620), decimals: 2 });
+                assert_eq!(i.date, NaiveDate::from_ymd_opt(2016, 6, 9).unwrap());
+                assert_eq!(i.country, "NLD");
+                assert_eq!(i.location, "Venlo");
+                assert_eq!(i.attribute, None);
+            },
+            ("Shot put", _) => {
+                assert_eq!(i.performance, Performance::Distance { metres: 5.98 });
+                assert_eq!(i.attribute, Some(EventAttribute::Weight(2.0)));
+            },
+            ("Long jump", true) => {
+                assert_eq!(i.performance, Performance::Distance { metres: 3.36 });
+                assert_eq!(i.wind_speed, Some(1.2));
+                assert_eq!(i.location, "Weert");
+            },
+            ("Long jump", false) => assert_eq!(i.performance, Performance::Distance { metres: 3.44 }),
+            ("60 meters hurdles", _) => {
+                assert_eq!(i.performance, Performance::Time { duration: Duration::from_millis(12400), decimals: 1 });
+                assert!(i.hand_measured);
+                match i.attribute {
+                    Some(EventAttribute::Height(h)) => assert!((h - 0.762).abs() < 1e-6),
+                    other => panic!("Expected a height, got {:?}", other),
+                }
+            },
+            ("Ball throw", _) => {
+                assert!(i.not_important);
+                assert_eq!(i.attribute, Some(EventAttribute::Weight(0.2)));
+            },
+            (other, _) => panic!("Unexpected personal best {}", other),
+        }
+    }
+
+    let long_jump = profile.graphs.iter().find(|v| v.event == "Long jump").unwrap();
+    assert_eq!(long_jump.specification, EventAttribute::All);
+    assert!(long_jump.points.contains(&(NaiveDate::from_ymd_opt(2016, 6, 9).unwrap(), Performance::Distance { metres: 3.44 })));
+    assert!(long_jump.points.contains(&(NaiveDate::from_ymd_opt(2016, 6, 25).unwrap(), Performance::Distance { metres: 3.36 })));
+
+    let shot_put = profile.graphs.iter().find(|v| v.event == "Shot put").unwrap();
+    assert_eq!(shot_put.specification, EventAttribute::Weight(2.0));
+
+    assert_eq!(profile.competitions.len(), 3);
+    assert_eq!(profile.competitions[2].participant_id, 0);
+}
+
+#[test]
+fn test_competition_details() {
+    let details = models::competition_details::parse(Html::parse_document(&fixture("synthetic_competition_main"))).unwrap();
+
+    assert_eq!(details.competition_id, 38406);
+    assert_eq!(details.organiser_club_id, Some(679));
+    assert_eq!(details.start_date, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+    assert_eq!(details.end_date, NaiveDate::from_ymd_opt(2024, 6, 2).unwrap());
+    assert_eq!(details.kind, Some(CompetitionKind::Outdoor));
+    assert_eq!(details.registration_deadline, NaiveDate::from_ymd_opt(2024, 5, 27).unwrap().and_hms_opt(23, 59, 0));
+    assert_eq!(details.fees[1], Fee { amount_cents: 1200, description: "per relay team".to_string() });
+    assert_eq!(details.venue.unwrap().country.as_deref(), Some("Netherlands"));
+
+    let contact = details.contact.unwrap();
+    assert_eq!(contact.name.as_deref(), Some("Jan Jansen"));
+    assert_eq!(contact.email.as_deref(), Some("wedstrijden@avgouda.nl"));
+    assert_eq!(contact.phone.as_deref(), Some("0182-123456"));
+
+    assert_eq!(details.documents.len(), 2);
+    assert_eq!(details.events[2].events, vec!["80m", "4x80m"]);
+    assert!(!details.registrations_open);
+    assert!(details.results_available);
+}
+
+#[test]
+fn test_event_results_rounds() {
+    let results = models::event_results::parse(Html::parse_document(&fixture("synthetic_event_results_100m"))).unwrap();
+
+    assert_eq!(results.competition_id, 45802);
+    assert_eq!(results.rounds.iter().map(|v| v.name.as_str()).collect::<Vec<_>>(), vec!["Heats", "Final"]);
+    assert_eq!(results.rounds[0].heats.len(), 2);
+    assert_eq!(results.rounds[0].heats[1].wind_speed, Some(-0.3));
+
+    let winner = &results.rounds[1].heats[0].results[0];
+    assert_eq!(winner.name, "Sara Peeters");
+    assert_eq!(winner.participant_id, Some(2418938));
+    assert_eq!(winner.lane, Some(4));
+    assert_eq!(winner.bib.as_deref(), Some("212"));
+    assert_eq!(winner.category.as_deref(), Some("WU20"));
+    assert_eq!(winner.performance, Some(Performance::Time { duration: Duration::from_millis(11870), decimals: 2 }));
+    assert_eq!(winner.performance.unwrap().to_string(), "11,87");
+    assert_eq!(winner.reaction_time, Some(0.151));
+
+    let dns = &results.rounds[0].heats[0].results[3];
+    assert_eq!(dns.place, None);
+    assert_eq!(dns.performance, None);
+    assert_eq!(dns.status, Some(ResultStatus::DidNotStart));
+    assert_eq!(dns.reaction_time, None);
+    assert_eq!(results.rounds[0].heats[1].results[2].status, Some(ResultStatus::Disqualified { rule: Some("TR16.8".to_string()) }));
+}
+
+#[test]
+fn test_start_list_heats() {
+    let start_list = models::start_list::parse(Html::parse_document(&fixture("synthetic_start_list_100m"))).unwrap();
+
+    assert_eq!(start_list.competition_id, 45802);
+    assert_eq!(start_list.start_group_name.as_deref(), Some("Women U20"));
+    assert_eq!(start_list.round.as_deref(), Some("Heats"));
+    assert_eq!(start_list.heats.iter().map(|v| v.entries.len()).collect::<Vec<_>>(), vec![4, 3]);
+
+    let entry = &start_list.heats[0].entries[1];
+    assert_eq!(entry.lane, Some(4));
+    assert_eq!(entry.name, "Sara Peeters");
+    assert_eq!(entry.participant_id, Some(2418938));
+    assert_eq!(entry.seed, Some(Performance::Time { duration: Duration::from_millis(11980), decimals: 2 }));
+    assert_eq!(entry.seed_text.as_deref(), Some("11,98"));
+
+    let unseeded = &start_list.heats[0].entries[3];
+    assert_eq!(unseeded.seed, None);
+    assert_eq!(unseeded.seed_text, None);
+}
+
+#[test]
+fn test_competition_timetable_days() {
+    let timetable = models::competition_timetable::parse(Html::parse_document(&fixture("synthetic_competition_timetable"))).unwrap();
+
+    assert_eq!(timetable.competition_id, 45802);
+    assert_eq!(timetable.days.len(), 2);
+    assert_eq!(timetable.days[0].date, NaiveDate::from_ymd_opt(2024, 9, 14).unwrap());
+    assert_eq!(timetable.days[0].label.as_deref(), Some("Saturday 14 September"));
+    assert_eq!(timetable.days[1].date, NaiveDate::from_ymd_opt(2024, 9, 15).unwrap());
+    assert_eq!(timetable.days.iter().map(|v| v.events.len()).collect::<Vec<_>>(), vec![4, 2]);
+
+    // the sortData is a UTC timestamp, the time in the table is CEST
+    let event = &timetable.days[0].events[1];
+    assert_eq!(event.time.to_rfc3339(), "2024-09-14T11:00:00+02:00");
+    assert_eq!(event.event_short, "100m");
+    assert_eq!(event.event_name, "100 meters");
+    assert_eq!(event.start_group_name, "Women U20");
+    assert_eq!(event.round.as_deref(), Some("Heats"));
+    assert_eq!(event.startlist_url, "https://www.athletics.app/wedstrijd/startlijst/45802/1/");
+    assert_eq!(timetable.days[0].events[2].round, None);
+}
+
+#[test]
+fn test_relay_team_members_and_results() {
+    let team = models::relay_team::parse(Html::parse_document(&fixture("synthetic_relay_team"))).unwrap();
+
+    assert_eq!(team.competition_id, 38406);
+    assert_eq!(team.name, "AV Gouda 1");
+    assert_eq!(team.club_id, Some(679));
+    assert_eq!(team.event_name.as_deref(), Some("4 x 100 meters"));
+    assert_eq!(team.members.iter().map(|v| v.leg).collect::<Vec<_>>(), vec![Some(1), Some(2), Some(3), Some(4), None]);
+    assert_eq!(team.members[0].name, "Lotte de Vries");
+    assert_eq!(team.members[0].participant_id, Some(3301201));
+
+    assert_eq!(team.results[0].place, Some(2));
+    let time = |millis| Performance::Time { duration: Duration::from_millis(millis), decimals: 2 };
+    assert_eq!(team.results[0].performance, Some(time(48710)));
+    assert_eq!(team.results[0].splits, vec![time(12400), time(24520), time(36800), time(48710)]);
+    assert_eq!(team.results[1].place, None);
+    assert_eq!(team.results[1].performance, None);
+    assert_eq!(team.results[1].status, Some(ResultStatus::Disqualified { rule: Some("TR24.19".to_string()) }));
+}
+
+#[test]
+fn test_club_members_and_competitions() {
+    let club = models::club::parse(Html::parse_document(&fixture("synthetic_club_main"))).unwrap();
+
+    assert_eq!(club.club_id, 679);
+    assert_eq!(club.name, "AV Gouda");
+    assert_eq!(club.city.as_deref(), Some("Gouda"));
+    assert_eq!(club.country.as_deref(), Some("Netherlands"));
+    assert_eq!(club.website.as_deref(), Some("https://www.avgouda.nl/"));
+    assert_eq!(club.members.iter().map(|v| v.athlete_id).collect::<Vec<_>>(), vec![3301201, 3301202, 3301206, 921275]);
+    assert_eq!(club.members[0].name, "Lotte de Vries");
+    assert_eq!(club.members[3].category, None);
+
+    assert_eq!(club.competitions.len(), 3);
+    assert_eq!(club.competitions[1].competition_id, 38406);
+    assert_eq!(club.competitions[1].date, NaiveDate::from_ymd_opt(2024, 6, 1).unwrap());
+    assert_eq!(club.competitions.iter().filter(|v| v.organiser).count(), 2);
+}
+
+#[test]
+fn test_registrations_club_id() {
+    let registrations = models::registrations_list_web::parse(Html::parse_document(&fixture("registrations_relay_teams"))).unwrap();
+
+    assert_eq!(registrations.iter().filter(|v| v.club_id == Some(679)).count(), 3);
+    assert!(registrations.iter().all(|v| v.club_id.is_some()));
+}
+
+#[test]
+fn test_event_catalogue_joins() {
+    // the results use the slug from the url and the timetable the short name
+    let results = event_results(&fixture("event_result_multiday")).unwrap();
+    for i in results.results.iter() {
+        assert!(results.timetable.iter().any(|v| v.canonical_event() == i.canonical_event()), "{} not in the timetable", i.event_name);
+    }
+
+    let profile = profile("profile_implements");
+    assert!(profile.personal_bests.iter().all(|v| !matches!(v.canonical_event(), Event::Other(_))));
+    for i in profile.graphs.iter() {
+        assert!(profile.personal_bests.iter().any(|v| v.canonical_event() == i.canonical_event()), "no personal best for {}", i.event);
+    }
+
+    for name in ["registrations_relay_teams", "registrations_event_status", "registrations_multiple_events"] {
+        for i in registrations(name) {
+            for (event, _) in i.canonical_events() {
+                assert!(!matches!(event, Event::Other(_)), "{:?} in {}", event, name);
+            }
+        }
+    }
+}
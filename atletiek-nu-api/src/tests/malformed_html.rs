@@ -0,0 +1,261 @@
+use scraper::Html;
+use crate::error::AtnError;
+use crate::models;
+
+type Parser = fn(&str) -> Result<(), AtnError>;
+
+const ATHLETE_EVENT_RESULT_HTML: &str = r#"<html><body>
+<div class="pageTitle"> Jan  Jansen </div>
+<div id="menubottom"><a class="hidden-xs" href="https://www.athletics.app/wedstrijd/main/38406/">Spring Games</a></div>
+<table id="uitslagentabel"><tbody>
+<tr><td><a href="https://www.athletics.app/wedstrijd/uitslagenonderdeel/38406/800m/">800m</a></td><td><span class="sortData" data="125.34"></span><span class="tipped">2:05,34</span></td><td>3</td></tr>
+<tr><td><a href="https://www.athletics.app/wedstrijd/uitslagenonderdeel/38406/Ver/">Ver</a></td><td><span class="sortData" data="5.12"></span><span class="tipped">5,12 +1,2m/s</span></td><td><span class="sortData" data="-2"></span><span class="tipped">x</span></td><td>1</td></tr>
+</tbody></table>
+<table class="chronoloogtabel"><tbody>
+<tr><td colspan="3">Saturday 1 June</td></tr>
+<tr><td><span class="sortData" data="1717236000"></span><a href="https://www.athletics.app/wedstrijd/startlijst/38406/1/">10:00</a></td><td><a><span class="hidden-xs">Men</span></a></td><td><a><span class="visible-xs-inline">800m</span><span class="hidden-xs">800 meters</span></a></td></tr>
+</tbody></table>
+</body></html>"#;
+
+const ATHLETE_PROFILE_HTML: &str = r##"<html><body>
+<div class="pageTitle">Marith  Siekman</div>
+<div id="records"><table id="persoonlijkerecords"><tbody>
+<tr class="notThatImportant"><td>Shot put <span class="subtext">2kg</span></td><td>5,98</td><td><span class="sortData" data="20160609Venlo (NLD)"></span></td></tr>
+<tr><td>Long jump</td><td>3,36 <span class="subtext"><span>+1,2m/s</span></span></td><td><span class="sortData" data="20160625Weert (NLD)"></span></td></tr>
+</tbody></table></div>
+<div id="wedstrijden"><table id="persoonlijkerecords"><tbody>
+<tr><td><a href="https://www.athletics.app/atleet/main/1793090/">Spring Games</a></td><td><span class="sortData" data="20160609Venlo"></span></td><td><span class="subtext"><span class="hidden-xs">Venlo <img src="/flags/nl.png" title='Netherlands<br><span class="subtext">Europe</span>'></span></span></td></tr>
+</tbody></table></div>
+<div id="graphTabs"><div>
+<div><a class="specification-selector" data-target="#specification-container-5-12">2kg</a></div>
+<script>chart({title: {text: 'Shot put'},subtitle: {text: '1 results'}, target: 'tab-pane#5', id: 'history-scores_12_1', data: [[Date.UTC(2016, 5, 9),5.98]]});</script>
+</div></div>
+</body></html>"##;
+
+const REGISTRATIONS_LIST_WEB_HTML: &str = r#"<html><body>
+<table class="deelnemerstabel"><thead><tr><th>Bib</th><th>Name</th><th>Club</th><th>Events</th><th>Relay team</th></tr></thead><tbody>
+<tr id="deelnemer_id=1793090"><td><span>44</span></td><td><a>J. <span>Jan Jansen</span> <span class="deelnemer-smalldetail">MSen - AV Gouda -</span> <span class="tipped">(OoC)</span></a></td><td><a>AV Gouda</a></td><td><span class="tipped" title="Checked-in">400m</span><span class="tipped" title="Accepted">400m_f</span></td><td><a href="https://www.athletics.app/estafetteteam/main/555/">AV Gouda 1</a></td></tr>
+</tbody></table>
+</body></html>"#;
+
+const COMPETITIONS_LIST_WEB_HTML: &str = r#"<html><body><table><tbody>
+<tr onclick="document.location.href='/wedstrijd/main/38406/'"><td class="datumCol"><span class="hidden-xs">Sat 01 JUN 2024</span></td><td class="eventnaam"><a><span><span class="eventnaam">Spring Games&nbsp;</span><span class="verenigingnaam">Gouda</span><span class="aantaldeelnemers">220 athletes</span></span></a></td><td><span>Results</span></td></tr>
+</tbody></table></body></html>"#;
+
+const COMPETITION_DETAILS_HTML: &str = r#"<html><body>
+<div id="menubottom"><a class="hidden-xs" href="https://www.athletics.app/wedstrijd/main/38406/">Spring Games</a></div>
+<div class="pageTitle">Spring Games</div>
+<a href="https://www.athletics.app/wedstrijd/inschrijven/38406/">Register</a>
+<table id="wedstrijdinfo"><tbody>
+<tr><th>Date</th><td>Sat 01 JUN 2024</td></tr>
+<tr><th>Organisation</th><td><a href="https://www.athletics.app/vereniging/main/679/">AV Gouda</a></td></tr>
+<tr><th>Venue</th><td>Groenhovenpark<br>Gouda <img src="/flags/nl.png" title='Netherlands<br><span class="subtext">Europe</span>'></td></tr>
+<tr><th>Registration deadline</th><td>Mon 27 MAY 2024 23:59</td></tr>
+<tr><th>Fees</th><td>&euro; 7,50 per event</td></tr>
+<tr><th>Contact</th><td>Jan Jansen<br><a href="mailto:jan@avgouda.nl">jan@avgouda.nl</a></td></tr>
+</tbody></table>
+<ul class="bijlagen"><li><a href="https://www.athletics.app/bijlage/38406/1/">Invitation.pdf</a></li></ul>
+<table class="onderdelentabel"><tbody><tr><td>Men Senior</td><td><span class="onderdeel">800m</span></td></tr></tbody></table>
+</body></html>"#;
+
+const EVENT_RESULTS_HTML: &str = r#"<html><body>
+<div id="menubottom"><a class="hidden-xs" href="https://www.athletics.app/wedstrijd/main/38406/">Spring Games</a></div>
+<div class="pageTitle">Long jump</div>
+<div class="uitslagen">
+<table class="uitslagentabel"><thead><tr><th>Pos.</th><th>Order</th><th>Name</th><th>Club</th><th>Result</th><th>Wind</th></tr></thead><tbody>
+<tr><td>1</td><td>3</td><td><a href="https://www.athletics.app/atleet/main/1793090/">Jan Jansen</a></td><td>AV Gouda</td><td><span class="sortData" data="6.12"></span><span class="tipped">6,12</span></td><td>+1,2m/s</td></tr>
+<tr><td>-</td><td>1</td><td><a href="https://www.athletics.app/atleet/main/1793091/">Piet Peters</a></td><td>AV Gouda</td><td><span class="sortData" data="-4"></span><span class="tipped">NM</span></td><td></td></tr>
+</tbody></table>
+</div>
+</body></html>"#;
+
+const CLUB_HTML: &str = r#"<html><head><link rel="canonical" href="https://www.athletics.app/vereniging/main/679/"></head><body>
+<div class="pageTitle">AV Gouda</div>
+<table id="verenigingsinfo"><tbody><tr><th>City</th><td>Gouda</td></tr></tbody></table>
+<table id="leden"><thead><tr><th>Name</th><th>Cat.</th></tr></thead><tbody>
+<tr><td><a href="https://www.athletics.app/atleet/profiel/921275/">Marith Siekman</a></td><td>VSen</td></tr>
+</tbody></table>
+<table id="wedstrijden"><thead><tr><th>Date</th><th>Competition</th><th>Venue</th></tr></thead><tbody>
+<tr><td><span class="sortData" data="20240601"></span>01-06-2024</td><td><a href="https://www.athletics.app/wedstrijd/main/38406/">Spring Games</a> <span class="organisator">Organiser</span></td><td>Gouda</td></tr>
+</tbody></table>
+</body></html>"#;
+
+const RELAY_TEAM_HTML: &str = r#"<html><body>
+<div id="menubottom"><a class="hidden-xs" href="https://www.athletics.app/wedstrijd/main/38406/">Spring Games</a></div>
+<div class="pageTitle">AV Gouda 2</div>
+<table id="teaminfo"><tbody><tr><th>Club</th><td><a href="https://www.athletics.app/vereniging/main/679/">AV Gouda</a></td></tr></tbody></table>
+<table id="teamleden"><thead><tr><th>Leg</th><th>Name</th></tr></thead><tbody>
+<tr><td>1</td><td><a href="https://www.athletics.app/atleet/main/1793090/">Jan Jansen</a></td></tr>
+</tbody></table>
+<table id="teamuitslagen"><thead><tr><th>Pos.</th><th>Result</th><th>Splits</th></tr></thead><tbody>
+<tr><td>1</td><td><span class="sortData" data="225.5"></span><span class="tipped">3:45,50</span></td><td><span class="tussentijd">56,20</span> <span class="tussentijd">1:52,90</span></td></tr>
+</tbody></table>
+</body></html>"#;
+
+// single day competition, without day headers
+const COMPETITION_TIMETABLE_HTML: &str = r#"<html><body>
+<div id="menubottom"><a class="hidden-xs" href="https://www.athletics.app/wedstrijd/main/38406/">Spring Games</a></div>
+<div class="pageTitle">Spring Games</div>
+<table class="chronoloogtabel"><tbody>
+<tr><td><span class="sortData" data="1717279200"></span><a href="https://www.athletics.app/wedstrijd/startlijst/38406/1/">00:00</a></td><td><a><span class="hidden-xs">Men</span></a></td><td><a><span class="visible-xs-inline">800m</span><span class="hidden-xs">800 meters</span></a></td></tr>
+</tbody></table>
+</body></html>"#;
+
+const START_LIST_HTML: &str = r#"<html><body>
+<div id="menubottom"><a class="hidden-xs" href="https://www.athletics.app/wedstrijd/main/38406/">Spring Games</a></div>
+<div class="pageTitle">800 meters</div>
+<div class="subtitle"><span class="startgroep">Men</span> <span class="ronde">Final</span></div>
+<div class="startlijst">
+<table class="startlijsttabel"><thead><tr><th>Order</th><th>Bib</th><th>Name</th><th>Club</th><th>PB</th></tr></thead><tbody>
+<tr><td>1</td><td>44</td><td><a href="https://www.athletics.app/atleet/main/1793090/">Jan Jansen</a></td><td>AV Gouda</td><td><span class="sortData" data="125.34"></span>2:05,34</td></tr>
+</tbody></table>
+</div>
+</body></html>"#;
+
+const ATHLETE_LIST_HTML: &str = r#"<div class="list-athletes"><ul><li><a onclick="koppel_id=12345"><div class="item-inner"><div class="item-title">Femke  Bol<br>24 years | AV Gouda</div></div></a></li></ul></div>"#;
+
+const COMPETITIONS_LIST_HTML: &str = r#"<div class="competitions-list"><ul><li><a href="/event&do=get&event_id=38406"><div class="item-inner"><div class="item-title"><h6>Spring Games</h6><div class="subtitle">Gouda</div><div class="item-footer">12 registrations</div></div></div></a></li></ul></div>"#;
+
+const REGISTRATIONS_LIST_HTML: &str = r#"<script class="list-content-registrations">&lt;ul&gt;&lt;li&gt;&lt;a onclick="show(deelnemer_id=5)"&gt;&lt;div class="item-inner"&gt;&lt;div class="item-title"&gt;Jan Jansen&lt;br&gt;MSen | AV Gouda&lt;/div&gt;&lt;div class="item-after"&gt;800m&lt;/div&gt;&lt;/div&gt;&lt;/a&gt;&lt;/li&gt;&lt;/ul&gt;</script>"#;
+
+fn documents() -> Vec<(&'static str, &'static str, Parser)> {
+    vec![
+        ("athlete_event_result", ATHLETE_EVENT_RESULT_HTML, |s| models::athlete_event_result::parse(Html::parse_document(s)).map(|_| ())),
+        ("athlete_profile", ATHLETE_PROFILE_HTML, |s| models::athlete_profile::parse(Html::parse_document(s)).map(|_| ())),
+        ("registrations_list_web", REGISTRATIONS_LIST_WEB_HTML, |s| models::registrations_list_web::parse(Html::parse_document(s)).map(|_| ())),
+        ("competitions_list_web", COMPETITIONS_LIST_WEB_HTML, |s| models::competitions_list_web::parse(Html::parse_document(s)).map(|_| ())),
+        ("competition_details", COMPETITION_DETAILS_HTML, |s| models::competition_details::parse(Html::parse_document(s)).map(|_| ())),
+        ("event_results", EVENT_RESULTS_HTML, |s| models::event_results::parse(Html::parse_document(s)).map(|_| ())),
+        ("club", CLUB_HTML, |s| models::club::parse(Html::parse_document(s)).map(|_| ())),
+        ("relay_team", RELAY_TEAM_HTML, |s| models::relay_team::parse(Html::parse_document(s)).map(|_| ())),
+        ("competition_timetable", COMPETITION_TIMETABLE_HTML, |s| models::competition_timetable::parse(Html::parse_document(s)).map(|_| ())),
+        ("start_list", START_LIST_HTML, |s| models::start_list::parse(Html::parse_document(s)).map(|_| ())),
+        ("athlete_list", ATHLETE_LIST_HTML, |s| models::athlete_list::parse(Html::parse_fragment(s)).map(|_| ())),
+        ("competitions_list", COMPETITIONS_LIST_HTML, |s| models::competitions_list::parse(Html::parse_fragment(s)).map(|_| ())),
+        ("registrations_list", REGISTRATIONS_LIST_HTML, |s| models::registrations_list::parse(Html::parse_fragment(s)).map(|_| ())),
+    ]
+}
+
+// Each mutation mimics a plausible change to the site
+fn mutations(html: &str) -> Vec<String> {
+    vec![
+        html.replace("href=", "data-href="),
+        html.replace("data=", "data-x="),
+        html.replace("sortData", "sort-data"),
+        html.replace("<td", "<th"),
+        html.replace("<a", "<b").replace("</a>", "</b>"),
+        html.replace("<span", "<em").replace("</span>", "</em>"),
+        html.replace(" - ", " "),
+        html.replace(" | ", " "),
+        html.replace("athletes", "atleten"),
+        html.replace("m/s", ""),
+        html.chars().map(|c| if c.is_ascii_digit() { 'x' } else { c }).collect(),
+        html.chars().filter(|c| !c.is_ascii_digit()).collect(),
+    ]
+}
+
+#[test]
+fn test_documents_parse() {
+    for (model, html, parser) in documents() {
+        if let Err(e) = parser(html) {
+            panic!("Failed to parse intact {} document: {}", model, e);
+        }
+    }
+}
+
+#[test]
+fn test_truncated_html_does_not_panic() {
+    for (_, html, parser) in documents() {
+        // every byte would be thorough but slow, as the parsers compile their regexes on each call
+        for cut in (0..html.len()).step_by(6) {
+            let _ = parser(&html[..cut]);
+        }
+    }
+}
+
+#[test]
+fn test_mutated_html_does_not_panic() {
+    for (_, html, parser) in documents() {
+        for mutated in mutations(html) {
+            let _ = parser(&mutated);
+        }
+    }
+}
+
+#[test]
+fn test_parse_error_names_location() {
+    let html = REGISTRATIONS_LIST_WEB_HTML.replace("deelnemer_id=1793090", "deelnemer_id=");
+
+    match models::registrations_list_web::parse(Html::parse_document(&html)) {
+        Err(AtnError::Parse { model, row, .. }) => {
+            assert_eq!(model, "registrations_list_web");
+            assert_eq!(row, Some(0));
+        },
+        other => panic!("Expected a parse error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_lenient_skips_broken_rows() {
+    let broken_row = r#"<tr id="deelnemer_id="><td><span>45</span></td><td><a>P. <span>Piet Peters</span></a></td><td><a>AV Gouda</a></td><td>800m</td><td></td></tr>"#;
+    let html = REGISTRATIONS_LIST_WEB_HTML.replace("</tbody>", &format!("{}</tbody>", broken_row));
+
+    assert!(models::registrations_list_web::parse(Html::parse_document(&html)).is_err());
+
+    let parsed = models::registrations_list_web::parse_lenient(Html::parse_document(&html)).unwrap();
+    assert_eq!(parsed.data.len(), 1);
+    assert_eq!(parsed.data[0].participant_id, 1793090);
+    assert_eq!(parsed.warnings.len(), 1);
+    assert_eq!(parsed.warnings[0].model, "registrations_list_web");
+    assert_eq!(parsed.warnings[0].row, Some(1));
+    assert!(parsed.warnings[0].skipped);
+    assert!(parsed.warnings[0].snippet.contains("Piet Peters"));
+}
+
+#[test]
+fn test_lenient_rows_count_per_table() {
+    let html = CLUB_HTML.replace("wedstrijd/main/38406/", "wedstrijd/main/");
+
+    let parsed = models::club::parse_lenient(Html::parse_document(&html)).unwrap();
+    assert_eq!(parsed.data.members.len(), 1);
+    assert!(parsed.data.competitions.is_empty());
+    assert_eq!(parsed.warnings.len(), 1);
+    assert_eq!(parsed.warnings[0].row, Some(0));
+
+    let html = RELAY_TEAM_HTML.replace("56,20", "fast");
+    let parsed = models::relay_team::parse_lenient(Html::parse_document(&html)).unwrap();
+    assert_eq!(parsed.data.members.len(), 1);
+    assert!(parsed.data.results.is_empty());
+    assert_eq!(parsed.warnings[0].row, Some(0));
+
+    let table = &EVENT_RESULTS_HTML[EVENT_RESULTS_HTML.find("<table").unwrap()..EVENT_RESULTS_HTML.find("</table>").unwrap() + "</table>".len()];
+    let heats = format!("<h3>Heat 1</h3>{}<h3>Heat 2</h3>{}", table, table.replace("atleet/main/1793090/", "atleet/main/x/"));
+    let parsed = models::event_results::parse_lenient(Html::parse_document(&EVENT_RESULTS_HTML.replace(table, &heats))).unwrap();
+    assert_eq!(parsed.data.rounds[0].heats[1].results.len(), 1);
+    assert_eq!(parsed.warnings[0].row, Some(0));
+}
+
+#[test]
+fn test_lenient_skips_document_without_name() {
+    let html = COMPETITION_DETAILS_HTML.replace("Invitation.pdf", "");
+
+    assert!(models::competition_details::parse(Html::parse_document(&html)).is_err());
+
+    let parsed = models::competition_details::parse_lenient(Html::parse_document(&html)).unwrap();
+    assert!(parsed.data.documents.is_empty());
+    assert_eq!(parsed.data.events.len(), 1);
+    assert_eq!(parsed.warnings.len(), 1);
+    assert!(parsed.warnings[0].skipped);
+}
+
+#[test]
+fn test_lenient_reports_soft_warnings() {
+    let html = COMPETITIONS_LIST_WEB_HTML.replace("<td><span>Results</span></td>", "");
+
+    let parsed = models::competitions_list_web::parse_lenient(Html::parse_document(&html)).unwrap();
+    assert_eq!(parsed.data.len(), 1);
+    assert!(!parsed.data[0].results_availible);
+    assert_eq!(parsed.warnings.len(), 1);
+    assert_eq!(parsed.warnings[0].field, "status");
+    assert!(!parsed.warnings[0].skipped);
+}
@@ -0,0 +1,392 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use chrono::NaiveDate;
+use futures::StreamExt;
+use mock_server::{Fault, MockServer};
+use reqwest::StatusCode;
+use crate::{AtnClient, AtnError, CompetitionKind, CompetitionSearch, Country, RateLimit, RequestInfo, RequestObserver, ResponseInfo, RetryPolicy};
+
+const FIXTURE_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/test-data");
+
+async fn start() -> (MockServer, AtnClient) {
+    let server = MockServer::start(FIXTURE_DIR).await.unwrap();
+    let client = AtnClient::builder()
+        .base_url(server.url())
+        .timeout(Some(Duration::from_millis(500)))
+        .retry_policy(RetryPolicy::default().base_delay(Duration::from_millis(10)))
+        .rate_limit(None)
+        .build()
+        .unwrap();
+
+    (server, client)
+}
+
+#[tokio::test]
+async fn test_mock_server_pages() {
+    let (server, client) = start().await;
+
+    let registrations = client.get_competition_registrations_web(&38406).await.unwrap();
+    assert_eq!(registrations.len(), 7);
+
+    let results = client.get_athlete_event_result(1793090).await.unwrap();
+    assert_eq!(results.get_total_points(), Some(1436));
+
+    let profile = client.get_athlete_profile(921275).await.unwrap();
+    assert_eq!(profile.name, "Marith Siekman");
+
+    let competitions = client.search_competitions_for_time_period(
+        NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 6, 2).unwrap(),
+        "",
+    ).await.unwrap();
+    assert_eq!(competitions.len(), 3);
+
+    let athletes = client.search_athletes("femke").await.unwrap();
+    assert_eq!(athletes.len(), 3);
+
+    let competition = client.get_competition(&38406).await.unwrap();
+    assert_eq!(competition.name, "Spring Relay Games");
+
+    let event = client.get_event_results(&45802, "100m").await.unwrap();
+    assert_eq!(event.rounds.len(), 2);
+
+    let club = client.get_club(679).await.unwrap();
+    assert_eq!(club.members.len(), 4);
+
+    let team = client.get_relay_team(9101).await.unwrap();
+    assert_eq!(team.members.len(), 5);
+
+    let timetable = client.get_competition_timetable(&45802).await.unwrap();
+    assert_eq!(timetable.days.len(), 2);
+
+    let start_list = client.get_start_list_from_url("https://www.athletics.app/wedstrijd/startlijst/45802/1/").await.unwrap();
+    assert_eq!(start_list.heats.len(), 2);
+    assert!(matches!(client.get_start_list_from_url("https://www.athletics.app/wedstrijd/main/45802/").await, Err(AtnError::InvalidUrl { .. })));
+
+    // one request for indoor and one for outdoor competitions
+    assert_eq!(server.requests().len(), 12);
+    assert!(server.requests()[0].starts_with("/wedstrijd/atleten/38406/"));
+}
+
+#[tokio::test]
+async fn test_mock_server_record_and_replay() {
+    let server = MockServer::start(FIXTURE_DIR).await.unwrap();
+    let dir = std::env::temp_dir().join("atn_test_record_and_replay");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let client = AtnClient::builder().base_url(server.url()).rate_limit(None).record_to(&dir).build().unwrap();
+    let profile = client.get_athlete_profile(921275).await.unwrap();
+
+    let replay = AtnClient::builder().replay_from(&dir).build().unwrap();
+    assert_eq!(replay.get_athlete_profile(921275).await.unwrap().name, profile.name);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_mock_server_competition_search() {
+    let (server, client) = start().await;
+    let start = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+    let end = NaiveDate::from_ymd_opt(2024, 6, 2).unwrap();
+
+    client.search_competitions_for_time_period(start, end, "").await.unwrap();
+    let search = CompetitionSearch::new(start, end).query("gouden spike").country(Country::Belgium);
+    assert_eq!(client.search_competitions_with(&search).await.unwrap().len(), 3);
+    client.search_competitions_with(&search.country("all")).await.unwrap();
+
+    let requests = server.requests();
+    assert!(requests[0].contains("country=NL&"));
+    assert!(requests[2].contains("country=BE&") && requests[2].contains("search=gouden%20spike&"));
+    assert!(requests[4].contains("country=all&"));
+}
+
+#[tokio::test]
+async fn test_mock_server_competition_kinds() {
+    let (server, client) = start().await;
+    server.route("/feeder.php?page=search&do=events&event_soort[]=out", "synthetic_feeder_long.html");
+    let search = CompetitionSearch::new(
+        NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 6, 2).unwrap(),
+    );
+
+    let competitions = client.search_competitions_with(&search).await.unwrap();
+    assert_eq!(competitions.len(), 163);
+    assert_eq!(competitions.iter().filter(|v| v.kind == Some(CompetitionKind::Indoor)).count(), 3);
+    assert!(competitions.windows(2).all(|v| v[0].date <= v[1].date));
+
+    let outdoor = client.search_competitions_with(&search.clone().kinds([CompetitionKind::Outdoor])).await.unwrap();
+    assert_eq!(outdoor.len(), 160);
+    assert!(outdoor.iter().all(|v| v.kind == Some(CompetitionKind::Outdoor)));
+
+    let results = client.search_competitions_with(&search.clone().kinds([CompetitionKind::Indoor]).results_available(true)).await.unwrap();
+    assert_eq!(results.iter().map(|v| v.competition_id).collect::<Vec<_>>(), vec![38406]);
+    let open = client.search_competitions_with(&search.clone().kinds([CompetitionKind::Indoor]).club_members_only(false)).await.unwrap();
+    assert_eq!(open.len(), 2);
+
+    client.search_competitions_with(&search.kinds([])).await.unwrap();
+    let requests = server.requests();
+    assert_eq!(requests.len(), 9);
+    assert!(requests[0].contains("event_soort[]=in&"));
+    assert!(requests[1].contains("event_soort[]=out&"));
+    assert!(requests[7].contains("event_soort[]=cross&"));
+    assert!(requests[8].contains("event_soort[]=weg&"));
+}
+
+#[tokio::test]
+async fn test_mock_server_competition_kinds_cross_and_road() {
+    // no cross country or road page is recorded yet, the short feeder page stands in for both
+    let (server, client) = start().await;
+    server.route("/feeder.php?page=search&do=events&event_soort[]=cross", "feeder_short.html");
+    server.route("/feeder.php?page=search&do=events&event_soort[]=weg", "feeder_short.html");
+    let search = CompetitionSearch::new(
+        NaiveDate::from_ymd_opt(2024, 6, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 6, 2).unwrap(),
+    );
+
+    let cross = client.search_competitions_with(&search.clone().kinds([CompetitionKind::CrossCountry])).await.unwrap();
+    assert!(!cross.is_empty() && cross.iter().all(|v| v.kind == Some(CompetitionKind::CrossCountry)));
+    let road = client.search_competitions_with(&search.clone().kinds([CompetitionKind::Road])).await.unwrap();
+    assert!(road.iter().all(|v| v.kind == Some(CompetitionKind::Road)));
+
+    // a competition in both responses is kept once, with the kind that was asked for first
+    let both = client.search_competitions_with(&search.kinds([CompetitionKind::Road, CompetitionKind::CrossCountry])).await.unwrap();
+    assert_eq!(both.len(), cross.len());
+    assert!(both.iter().all(|v| v.kind == Some(CompetitionKind::Road)));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 4);
+    assert!(requests[0].contains("event_soort[]=cross&"));
+    assert!(requests[1].contains("event_soort[]=weg&"));
+    assert!(requests[2].contains("event_soort[]=weg&") && requests[3].contains("event_soort[]=cross&"));
+}
+
+#[tokio::test]
+async fn test_mock_server_competition_range() {
+    let (server, client) = start().await;
+    let search = CompetitionSearch::new(
+        NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+        NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+    ).kinds([CompetitionKind::Outdoor]);
+
+    // every window gets the same three competitions
+    let competitions = client.search_competitions_range(&search).await.unwrap();
+    assert_eq!(competitions.iter().map(|v| v.competition_id).collect::<Vec<_>>(), vec![38406, 38436, 38441]);
+    assert_eq!(server.requests().len(), 27);
+    assert!(server.requests().iter().any(|v| v.contains("startDate=1735516800&endDate=1735603200")));
+
+    let windows: Vec<_> = client.search_competitions_stream(&search).collect().await;
+    assert_eq!(windows.len(), 27);
+    assert!(windows.iter().all(|v| v.as_ref().unwrap().len() == 3));
+
+    server.fault_next(Fault::Status(404));
+    assert!(matches!(client.search_competitions_range(&search).await, Err(AtnError::NotFound { .. })));
+}
+
+#[tokio::test]
+async fn test_mock_server_not_found() {
+    let (server, client) = start().await;
+
+    match client.get_athlete_profile(1).await {
+        Err(AtnError::NotFound { url }) => assert!(url.ends_with("/atleet/profiel/1")),
+        other => panic!("Expected not found, got {:?}", other.map(|v| v.name)),
+    }
+
+    // not worth retrying
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_mock_server_http_errors() {
+    let (server, client) = start().await;
+
+    server.fault(Fault::Status(500));
+    match client.get_athlete_profile(921275).await {
+        Err(AtnError::HttpStatus { status, .. }) => assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR),
+        other => panic!("Expected a 500, got {:?}", other.map(|v| v.name)),
+    }
+    assert_eq!(server.requests().len(), 3);
+
+    server.clear_faults();
+    server.fault(Fault::Status(403));
+    match client.get_athlete_profile(921275).await {
+        Err(AtnError::HttpStatus { status, .. }) => assert_eq!(status, StatusCode::FORBIDDEN),
+        other => panic!("Expected a 403, got {:?}", other.map(|v| v.name)),
+    }
+    assert_eq!(server.requests().len(), 4);
+}
+
+#[tokio::test]
+async fn test_mock_server_retry() {
+    let (server, client) = start().await;
+
+    server.fault_next(Fault::Status(502));
+    server.fault_next(Fault::Status(503));
+    assert_eq!(client.get_athlete_profile(921275).await.unwrap().name, "Marith Siekman");
+    assert_eq!(server.requests().len(), 3);
+
+    server.fault_next(Fault::RateLimited { retry_after: 1 });
+    let start = std::time::Instant::now();
+    assert!(client.get_athlete_profile(921275).await.is_ok());
+    assert!(start.elapsed() >= Duration::from_secs(1));
+
+    let client = AtnClient::builder()
+        .base_url(server.url())
+        .retry_policy(RetryPolicy::none())
+        .rate_limit(None)
+        .build()
+        .unwrap();
+    server.fault_next(Fault::Status(502));
+    assert!(client.get_athlete_profile(921275).await.is_err());
+}
+
+#[tokio::test]
+async fn test_mock_server_rate_limit() {
+    let server = MockServer::start(FIXTURE_DIR).await.unwrap();
+    // the budget starts refilling when the client is built
+    let start = std::time::Instant::now();
+    let client = AtnClient::builder()
+        .base_url(server.url())
+        .rate_limit(Some(RateLimit::new(1, Duration::from_millis(200))))
+        .build()
+        .unwrap();
+
+    // clones share the budget
+    let other = client.clone();
+    let (a, b) = tokio::join!(client.get_athlete_profile(921275), other.get_athlete_profile(921275));
+    let (c, d) = tokio::join!(client.search_athletes("femke"), other.search_athletes("femke"));
+    assert!(a.is_ok() && b.is_ok() && c.is_ok() && d.is_ok());
+    assert!(start.elapsed() >= Duration::from_millis(600));
+}
+
+#[tokio::test]
+async fn test_mock_server_max_concurrent_requests() {
+    let server = MockServer::start(FIXTURE_DIR).await.unwrap();
+    server.fault(Fault::Delay(Duration::from_millis(200)));
+    let client = AtnClient::builder()
+        .base_url(server.url())
+        .rate_limit(None)
+        .max_concurrent_requests(Some(1))
+        .build()
+        .unwrap();
+
+    let start = std::time::Instant::now();
+    let (a, b, c) = tokio::join!(client.search_athletes("a"), client.search_athletes("b"), client.search_athletes("c"));
+    assert!(a.is_ok() && b.is_ok() && c.is_ok());
+    assert!(start.elapsed() >= Duration::from_millis(600));
+}
+
+#[derive(Clone, Default)]
+struct RecordingObserver {
+    events: Arc<Mutex<Vec<String>>>,
+}
+
+impl RequestObserver for RecordingObserver {
+    fn on_start(&self, request: &RequestInfo) {
+        self.events.lock().unwrap().push(format!("start {}", request.attempt));
+    }
+
+    fn on_retry(&self, request: &RequestInfo, _delay: Duration, reason: &str) {
+        self.events.lock().unwrap().push(format!("retry {} {}", request.attempt, reason));
+    }
+
+    fn on_response(&self, _request: &RequestInfo, response: &ResponseInfo) {
+        self.events.lock().unwrap().push(format!("response {} {}", response.status.as_u16(), response.bytes > 0));
+    }
+
+    fn on_error(&self, _request: &RequestInfo, _error: &AtnError) {
+        self.events.lock().unwrap().push("error".to_string());
+    }
+
+    fn on_parse_success(&self, _request: &RequestInfo) {
+        self.events.lock().unwrap().push("parsed".to_string());
+    }
+
+    fn on_parse_failure(&self, _request: &RequestInfo, _error: &AtnError) {
+        self.events.lock().unwrap().push("parse failed".to_string());
+    }
+}
+
+#[tokio::test]
+async fn test_mock_server_observer() {
+    let server = MockServer::start(FIXTURE_DIR).await.unwrap();
+    let observer = RecordingObserver::default();
+    let client = AtnClient::builder()
+        .base_url(server.url())
+        .timeout(Some(Duration::from_millis(500)))
+        .retry_policy(RetryPolicy::default().base_delay(Duration::from_millis(10)).max_attempts(2))
+        .rate_limit(None)
+        .observer(observer.clone())
+        .build()
+        .unwrap();
+
+    server.fault_next(Fault::Status(502));
+    client.get_athlete_profile(921275).await.unwrap();
+    assert_eq!(*observer.events.lock().unwrap(), vec![
+        "start 1", "response 502 false", "retry 1 HTTP status 502 Bad Gateway", "start 2", "response 200 true", "parsed",
+    ]);
+
+    observer.events.lock().unwrap().clear();
+    server.fault_next(Fault::Delay(Duration::from_secs(1)));
+    server.fault_next(Fault::MalformedHtml);
+    let _ = client.get_athlete_event_result(2418938).await;
+    let events = observer.events.lock().unwrap().clone();
+    assert_eq!(events[..2], ["start 1", "error"]);
+    assert!(events[2].starts_with("retry 1"));
+    assert_eq!(events[3..5], ["start 2", "response 200 true"]);
+}
+
+#[tokio::test]
+async fn test_mock_server_malformed_html() {
+    let (server, client) = start().await;
+    server.fault(Fault::MalformedHtml);
+
+    for id in [1793090, 1734217, 2418938] {
+        match client.get_athlete_event_result(id).await {
+            Ok(_) | Err(AtnError::Parse { .. }) | Err(AtnError::NoResultsYet) => (),
+            Err(e) => panic!("Unexpected error for a cut off page: {}", e),
+        }
+    }
+
+    assert!(client.get_competition_registrations_web_lenient(&38406).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_mock_server_timeout() {
+    let (server, client) = start().await;
+    server.fault(Fault::Delay(Duration::from_secs(2)));
+
+    match client.search_athletes("femke").await {
+        Err(AtnError::Network(e)) => assert!(e.is_timeout()),
+        other => panic!("Expected a timeout, got {:?}", other),
+    }
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn test_mock_server_blocking() {
+    use crate::blocking::BlockingClient;
+
+    // the server needs a runtime, the blocking client brings its own
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockServer::start(FIXTURE_DIR)).unwrap();
+    let client = BlockingClient::new(AtnClient::builder().base_url(server.url()).rate_limit(None).build().unwrap()).unwrap();
+
+    assert_eq!(client.get_competition_registrations_web(&38406).unwrap().len(), 7);
+    assert_eq!(client.get_athlete_profile(921275).unwrap().name, "Marith Siekman");
+    assert!(client.get_athlete_profile(1).unwrap_err().is_not_found());
+
+    // clones share the runtime and can be used from other threads
+    let clone = client.clone();
+    let athletes = std::thread::spawn(move || clone.search_athletes("femke")).join().unwrap();
+    assert_eq!(athletes.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_mock_server_club_competition_results() {
+    let (server, client) = start().await;
+
+    let results = client.get_club_competition_results(679, &38406).await.unwrap();
+    assert_eq!(results.athletes.iter().map(|v| v.registration.participant_id).collect::<Vec<_>>(), vec![3301201, 3301202, 3301206]);
+    // the mock server has no page for the last athlete
+    assert_eq!(results.athletes.iter().map(|v| v.results.is_some()).collect::<Vec<_>>(), vec![true, true, false]);
+    assert_eq!(server.requests().len(), 4);
+}
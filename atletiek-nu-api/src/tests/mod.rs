@@ -1,3 +1,7 @@
+mod fixtures;
+mod malformed_html;
+mod mock_server;
+
 use std::collections::HashMap;
 use std::ops::Add;
 use std::time::Duration;
@@ -5,12 +9,47 @@ use chrono::NaiveDate;
 use tokio;
 use regex::Regex;
 use tokio::time::Instant;
-use crate::{get_competition_registrations_web, get_athlete_event_result, get_athlete_profile};
-use crate::models::athlete_event_result::{DnfReason, EventResultItem};
+use crate::{get_competition_registrations_web, get_athlete_event_result, get_athlete_profile, AtnClient};
+use crate::models::athlete_event_result::EventResultItem;
 use crate::models::athlete_profile::EventAttribute;
 use crate::models::registrations_list_web::EventStatus;
+use crate::performance::{Performance, ResultStatus};
+
+#[test]
+fn test_client_base_url() {
+    let client = AtnClient::builder()
+        .base_url("http://localhost:8000/")
+        .build()
+        .unwrap();
+
+    assert_eq!(client.base_url(), "http://localhost:8000");
+}
+
+#[tokio::test]
+async fn test_replay_transport() {
+    let dir = std::env::temp_dir().join("atn_test_replay_transport");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join(crate::transport::recording_file_name(
+            "/athleteapp.php?page=athletes&do=searchresults&name=femke&language=en_GB&version=1.16&improvePerformance=0",
+        )),
+        r#"<div class="list-athletes"><ul><li><a onclick="koppel_id=12345"><div class="item-inner"><div class="item-title">Femke  Bol<br>24 years | AV Gouda</div></div></a></li></ul></div>"#,
+    ).unwrap();
+
+    let client = AtnClient::builder().replay_from(&dir).build().unwrap();
+    let athletes = client.search_athletes("femke").await.unwrap();
+
+    assert_eq!(athletes.len(), 1);
+    assert_eq!(athletes[0].id, 12345);
+    assert_eq!(athletes[0].name, "Femke Bol");
+    assert_eq!(athletes[0].age, 24);
+
+    // nothing recorded for this one, so it has to fail instead of going to the network
+    assert!(client.search_athletes("dafne").await.is_err());
+}
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_get_participant_list_39657() {
             let participants = get_competition_registrations_web(&39657)
         .await
@@ -20,6 +59,7 @@ async fn test_get_participant_list_39657() {
 }
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_get_participant_list_38681() {
     let participants = get_competition_registrations_web(&38681)
         .await
@@ -29,6 +69,7 @@ async fn test_get_participant_list_38681() {
 }
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_get_results_combined_event_with_dnf_1793090() {
     let results = get_athlete_event_result(1793090)
         .await
@@ -44,43 +85,39 @@ async fn test_get_results_combined_event_with_dnf_1793090() {
             assert_eq!(i.items.len(), 2);
             assert!(i.items.contains(&EventResultItem::Measurement {
                 wind_speed: None,
-                result: 9999998.0,
-                dnf: true,
-                dnf_reason: Some(DnfReason::DataAboveThreshold {
-                    threshold: 10000.0
-                })
+                result: None,
+                status: Some(ResultStatus::DidNotFinish),
+                attempt: None,
+                heights: vec![],
             }));
         }
     }
 }
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_get_results_dnf_1734217() {
     let results = get_athlete_event_result(1734217)
         .await
         .unwrap();
 
     assert_eq!(results.results.len(), 3); // LJ, SP, DT
-    let mut expected_dnf_counts: HashMap<String, usize> = HashMap::new();
-    expected_dnf_counts.insert("Ver".to_string(), 2);
-    expected_dnf_counts.insert("Kogel".to_string(), 0);
-    expected_dnf_counts.insert("Discus".to_string(), 4);
+    let mut expected_foul_counts: HashMap<String, usize> = HashMap::new();
+    expected_foul_counts.insert("Ver".to_string(), 2);
+    expected_foul_counts.insert("Kogel".to_string(), 0);
+    expected_foul_counts.insert("Discus".to_string(), 4);
 
     for result in results.results {
-        let dnf_count = result.items.iter().filter(|v| match v {
-            EventResultItem::Measurement { dnf, dnf_reason, .. } => if *dnf {
-                assert_eq!(*dnf_reason.as_ref().unwrap(), DnfReason::DataBelowZero); // in this case, they are all -2
-                true
-            } else { false },
-            _ => false,
-        }).count();
-
-        let expected_count = expected_dnf_counts.get(&result.event_name).unwrap();
-        assert_eq!(dnf_count, *expected_count);
+        // the site shows these as `x`, with -2 as the sortData
+        let foul_count = result.items.iter().filter(|v| matches!(v, EventResultItem::Measurement { status: Some(ResultStatus::Foul), .. })).count();
+
+        let expected_count = expected_foul_counts.get(&result.event_name).unwrap();
+        assert_eq!(foul_count, *expected_count);
     }
 }
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_multiple_event_registrations_40258() {
     let registrations = get_competition_registrations_web(&40258).await.unwrap();
 
@@ -96,6 +133,7 @@ async fn test_multiple_event_registrations_40258() {
 }
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_event_status_38436() {
     let registrations = get_competition_registrations_web(&38436).await.unwrap();
 
@@ -122,6 +160,7 @@ async fn test_event_status_38436() {
 }
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_get_participant_list_38679() {
     let registrations =  get_competition_registrations_web(&38679).await.unwrap();
 
@@ -133,6 +172,7 @@ async fn test_get_participant_list_38679() {
 }
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_relay_teams_38406() {
     let registrations = get_competition_registrations_web(&38406).await.unwrap();
 
@@ -146,6 +186,7 @@ async fn test_relay_teams_38406() {
 }
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_profile_921275() {
     let profile = get_athlete_profile(921275).await.unwrap();
 
@@ -154,7 +195,7 @@ async fn test_profile_921275() {
     for i in profile.personal_bests {
         match i.event.as_str() {
             "60 meters" => {
-                assert_eq!(i.performance, 9.62);
+                assert_eq!(i.performance, Performance::Time { duration: Duration::from_millis(9620), decimals: 2 });
                 assert_eq!(i.hand_measured, false);
                 assert_eq!(i.date, NaiveDate::from_ymd_opt(2016, 06, 09).unwrap());
                 assert_eq!(i.country, "NLD");
@@ -162,7 +203,7 @@ async fn test_profile_921275() {
                 assert_eq!(i.attribute, None);
             },
             "Shot put" => {
-                assert_eq!(i.performance, 5.98);
+                assert_eq!(i.performance, Performance::Distance { metres: 5.98 });
                 assert_eq!(i.hand_measured, false);
                 assert_eq!(i.date, NaiveDate::from_ymd_opt(2016, 06, 09).unwrap());
                 assert_eq!(i.country, "NLD");
@@ -171,14 +212,14 @@ async fn test_profile_921275() {
             },
             "Long jump" => {
                 if i.wind_speed.is_some() {
-                    assert_eq!(i.performance, 3.36);
+                    assert_eq!(i.performance, Performance::Distance { metres: 3.36 });
                     assert_eq!(i.hand_measured, false);
                     assert_eq!(i.date, NaiveDate::from_ymd_opt(2016, 06, 25).unwrap());
                     assert_eq!(i.country, "NLD");
                     assert_eq!(i.location, "Weert");
                     assert_eq!(i.attribute, None);
                 } else {
-                    assert_eq!(i.performance, 3.44);
+                    assert_eq!(i.performance, Performance::Distance { metres: 3.44 });
                     assert_eq!(i.hand_measured, false);
                     assert_eq!(i.date, NaiveDate::from_ymd_opt(2016, 06, 09).unwrap());
                     assert_eq!(i.country, "NLD");
@@ -195,8 +236,8 @@ async fn test_profile_921275() {
             "Long jump" => {
                 assert_eq!(graph.points.len(), 2);
                 assert_eq!(graph.specification, EventAttribute::All);
-                assert!(graph.points.contains(&(NaiveDate::from_ymd_opt(2016, 6, 9).unwrap(), 3.44)));
-                assert!(graph.points.contains(&(NaiveDate::from_ymd_opt(2016, 6, 25).unwrap(), 3.36)))
+                assert!(graph.points.contains(&(NaiveDate::from_ymd_opt(2016, 6, 9).unwrap(), Performance::Distance { metres: 3.44 })));
+                assert!(graph.points.contains(&(NaiveDate::from_ymd_opt(2016, 6, 25).unwrap(), Performance::Distance { metres: 3.36 })))
             },
             _ => (),
         }
@@ -204,6 +245,7 @@ async fn test_profile_921275() {
 }
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_profile_parsing() {
     let profiles = [862577, 876749, 871514, 862980, 871317];
 
@@ -216,6 +258,7 @@ async fn test_profile_parsing() {
 }
 
 #[tokio::test]
+#[ignore = "hits the live site"]
 async fn test_multiday_event_parsing_2418938() {
     let _ = get_athlete_event_result(2418938).await.unwrap();
 }
\ No newline at end of file
@@ -0,0 +1,212 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use async_trait::async_trait;
+use log::{info, trace, warn};
+use reqwest::header::HeaderMap;
+use reqwest::{Client, StatusCode, Url};
+use crate::error::{AtnError, Result};
+
+// Longer names get truncated and suffixed with a hash of the full path
+const MAX_RECORDING_NAME_LEN: usize = 120;
+
+pub struct TransportResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+    /// Where the request ended up, if it was redirected
+    pub redirected_to: Option<Url>,
+}
+
+/// Fetches pages for the client, allows swapping the live site for recordings
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait Transport: Send + Sync {
+    async fn get(&self, url: &str) -> Result<TransportResponse>;
+}
+
+/// Sends requests to the live site using reqwest
+pub struct ReqwestTransport {
+    client: Client,
+}
+
+impl ReqwestTransport {
+    pub fn new(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Transport for ReqwestTransport {
+    async fn get(&self, url: &str) -> Result<TransportResponse> {
+        let res = self.client.get(url).send().await?;
+        let status = res.status();
+        let headers = res.headers().clone();
+        let redirected_to = match Url::parse(url) {
+            Ok(v) if v != *res.url() => Some(res.url().clone()),
+            _ => None,
+        };
+        let body = res.text().await?;
+
+        Ok(TransportResponse { status, headers, body, redirected_to })
+    }
+}
+
+/// Passes requests to another transport and writes every successful response to a directory,
+/// so it can be read back by [`ReplayTransport`]
+pub struct RecordTransport {
+    inner: Arc<dyn Transport>,
+    dir: PathBuf,
+}
+
+impl RecordTransport {
+    /// The directory is created with the first recording
+    pub fn new<P: Into<PathBuf>>(inner: Arc<dyn Transport>, dir: P) -> Self {
+        Self { inner, dir: dir.into() }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Transport for RecordTransport {
+    async fn get(&self, url: &str) -> Result<TransportResponse> {
+        let res = self.inner.get(url).await?;
+
+        if res.status.is_success() {
+            fs::create_dir_all(&self.dir)
+                .await
+                .map_err(|e| AtnError::io(format!("Failed to create recording directory {}", self.dir.display()), e))?;
+            let path = self.dir.join(recording_file_name(url));
+            fs::write(&path, &res.body)
+                .await
+                .map_err(|e| AtnError::io(format!("Failed to write recording {}", path.display()), e))?;
+            info!("Recorded {} to {}", url, path.display());
+        } else {
+            warn!("Not recording {}, got status {}", url, res.status);
+        }
+
+        Ok(res)
+    }
+}
+
+/// Serves responses from a directory written by [`RecordTransport`], never touches the network
+pub struct ReplayTransport {
+    dir: PathBuf,
+}
+
+impl ReplayTransport {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl Transport for ReplayTransport {
+    async fn get(&self, url: &str) -> Result<TransportResponse> {
+        let path = self.dir.join(recording_file_name(url));
+        trace!("Replaying {} from {}", url, path.display());
+        let body = fs::read_to_string(&path)
+            .await
+            .map_err(|e| AtnError::io(format!("No recording for {} ({})", url, path.display()), e))?;
+
+        Ok(TransportResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body,
+            redirected_to: None,
+        })
+    }
+}
+
+/// File name a recording of `url` is stored under. Only the path and query are used,
+/// so recordings made against one base url can be replayed against another. The readable
+/// part is lossy, so a hash of the full path and query is always appended to keep names unique.
+pub fn recording_file_name(url: &str) -> String {
+    let path = match url.find("://") {
+        Some(i) => match url[i + 3..].find('/') {
+            Some(j) => &url[i + 3 + j..],
+            None => "/",
+        },
+        None => url,
+    };
+
+    let mut name: String = path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string();
+
+    if name.is_empty() {
+        name.push_str("index");
+    }
+
+    name.truncate(MAX_RECORDING_NAME_LEN - 17);
+    name.push_str(&format!("_{:016x}", fnv1a(path.as_bytes())));
+
+    name.push_str(".html");
+    name
+}
+
+// tokio runs these on its blocking pool, there's no file system to block on cloudflare workers
+#[cfg(not(target_arch = "wasm32"))]
+mod fs {
+    pub use tokio::fs::{create_dir_all, read_to_string, write};
+}
+
+#[cfg(target_arch = "wasm32")]
+mod fs {
+    use std::io;
+    use std::path::Path;
+
+    pub async fn create_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
+        std::fs::create_dir_all(path)
+    }
+
+    pub async fn read_to_string<P: AsRef<Path>>(path: P) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    pub async fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+        std::fs::write(path, contents)
+    }
+}
+
+// std's hasher isn't stable between releases, and the names end up on disk
+fn fnv1a(data: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in data {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[test]
+fn test_recording_file_name() {
+    let name = recording_file_name("https://www.athletics.app/atleet/main/1793090/");
+    assert!(name.starts_with("atleet_main_1793090_"));
+    assert!(name.ends_with(".html"));
+    assert_eq!(name, recording_file_name("http://localhost:8000/atleet/main/1793090/"));
+    assert!(recording_file_name("https://www.athletics.app/feeder.php?page=search&do=events&search=a")
+        .starts_with("feeder.php_page_search_do_events_search_a_"));
+
+    let long = format!("https://www.athletics.app/feeder.php?search={}", "a".repeat(200));
+    let name = recording_file_name(&long);
+    assert_eq!(name.len(), MAX_RECORDING_NAME_LEN + ".html".len());
+    assert_ne!(name, recording_file_name(&format!("{}b", long)));
+}
+
+#[test]
+fn test_recording_file_name_collisions() {
+    let names = ["a b", "a_b", "a/b", "a%20b"]
+        .iter()
+        .map(|q| recording_file_name(&format!("https://www.athletics.app/feeder.php?search={}", q)))
+        .collect::<std::collections::HashSet<_>>();
+    assert_eq!(names.len(), 4);
+}
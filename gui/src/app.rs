@@ -5,16 +5,19 @@ use eframe::{CreationContext, Frame};
 use egui::{Context, Id, TopBottomPanel, Ui};
 use std::collections::HashMap;
 use std::collections::VecDeque;
-use std::sync::mpsc::{sync_channel, Receiver};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use atletiek_nu_api::blocking::BlockingClient;
+use atletiek_nu_api::{AtnClient, RequestInfo, RequestObserver, ResponseInfo, Url};
 
 pub struct App {
     windows: Vec<(Id, Box<dyn Window>)>,
     event_queue: VecDeque<AppEvent>,
     counter: usize,
-    req_rx: Receiver<(usize, atletiek_nu_api::Request)>,
-    sta_rx: Receiver<(usize, atletiek_nu_api::StatusCode)>,
+    request_log: RequestLog,
     requests: HashMap<usize, RequestState>,
+    // revision of the log `requests` was copied at
+    requests_revision: usize,
 }
 
 pub enum AppEvent {
@@ -22,16 +25,83 @@ pub enum AppEvent {
     PopWindow(Id),
 }
 
+#[derive(Clone)]
 pub enum RequestState {
     Pending {
-        req: atletiek_nu_api::Request,
+        url: Url,
+        attempt: u32,
         started_at: Instant,
     },
     Finished {
-        req: atletiek_nu_api::Request,
+        url: Url,
+        attempt: u32,
         code: atletiek_nu_api::StatusCode,
         duration: Duration,
     },
+    Failed {
+        url: Url,
+        attempt: u32,
+        error: String,
+    },
+}
+
+// Collects the requests of the shared client, so the requests window can show them
+#[derive(Clone, Default)]
+struct RequestLog {
+    inner: Arc<Mutex<RequestLogInner>>,
+}
+
+#[derive(Default)]
+struct RequestLogInner {
+    // bumped on every change, so the app only copies the requests when there's something new
+    revision: usize,
+    requests: HashMap<usize, RequestState>,
+}
+
+impl RequestLog {
+    fn insert(&self, id: usize, state: RequestState) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.requests.insert(id, state);
+        inner.revision += 1;
+    }
+}
+
+impl RequestObserver for RequestLog {
+    fn on_start(&self, request: &RequestInfo) {
+        self.insert(
+            request.id,
+            RequestState::Pending {
+                url: request.url.clone(),
+                attempt: request.attempt,
+                started_at: Instant::now(),
+            },
+        );
+        log::info!("Inserted new request {}", request.id);
+    }
+
+    fn on_response(&self, request: &RequestInfo, response: &ResponseInfo) {
+        self.insert(
+            request.id,
+            RequestState::Finished {
+                url: request.url.clone(),
+                attempt: request.attempt,
+                code: response.status,
+                duration: response.duration,
+            },
+        );
+        log::info!("Updated request {} status to {}", request.id, response.status);
+    }
+
+    fn on_error(&self, request: &RequestInfo, error: &atletiek_nu_api::AtnError) {
+        self.insert(
+            request.id,
+            RequestState::Failed {
+                url: request.url.clone(),
+                attempt: request.attempt,
+                error: error.to_string(),
+            },
+        );
+    }
 }
 
 pub trait Window {
@@ -52,18 +122,24 @@ struct AppCtxProvider<'a> {
 
 impl App {
     pub fn new(_: &CreationContext) -> Self {
-        let (req_tx, req_rx) = sync_channel::<(usize, atletiek_nu_api::Request)>(1000);
-        let (sta_tx, sta_rx) = sync_channel::<(usize, atletiek_nu_api::StatusCode)>(1000);
-
-        atletiek_nu_api::set_request_callback(req_tx, sta_tx);
+        let request_log = RequestLog::default();
+        let client = AtnClient::builder()
+            .from_env()
+            .observer(request_log.clone())
+            .build()
+            .expect("Failed to build client");
+
+        if BlockingClient::set_shared(BlockingClient::new(client).expect("Failed to build blocking client")).is_err() {
+            log::warn!("Shared client was already set, requests won't show up");
+        }
 
         Self {
             windows: Vec::new(),
             event_queue: VecDeque::new(),
             counter: 0,
-            req_rx,
-            sta_rx,
+            request_log,
             requests: HashMap::new(),
+            requests_revision: 0,
         }
     }
 
@@ -93,33 +169,10 @@ impl App {
     }
 
     fn update_requests(&mut self) {
-        for i in self.req_rx.try_iter() {
-            self.requests.insert(
-                i.0,
-                RequestState::Pending {
-                    req: i.1,
-                    started_at: Instant::now(),
-                },
-            );
-            log::info!("Inserted new request {}", i.0);
-        }
-
-        for i in self.sta_rx.try_iter() {
-            let req = self.requests.remove(&i.0).unwrap();
-            let new_req = match req {
-                RequestState::Pending { req, started_at } => RequestState::Finished {
-                    req,
-                    code: i.1,
-                    duration: started_at.elapsed(),
-                },
-                other => {
-                    log::warn!("Tried to update non-pending request!");
-                    other
-                }
-            };
-            self.requests.insert(i.0, new_req);
-
-            log::info!("Updated request {} status to {}", i.0, i.1);
+        let inner = self.request_log.inner.lock().unwrap();
+        if inner.revision != self.requests_revision {
+            self.requests = inner.requests.clone();
+            self.requests_revision = inner.revision;
         }
     }
 
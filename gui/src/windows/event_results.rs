@@ -1,8 +1,9 @@
 use crate::app::{AppCtx, Window};
 use crate::async_resource::AsyncResource;
+use atletiek_nu_api::models::athlete_event_result::EventResultItem;
+use atletiek_nu_api::models::competition_timetable::TIMEZONE;
 use atletiek_nu_api::AthleteEventResults;
 use egui::{Color32, RichText, Ui};
-use std::collections::{BTreeMap, HashMap};
 
 pub struct EventResultsWindow {
     id: u32,
@@ -32,7 +33,7 @@ impl Window for EventResultsWindow {
 
     fn on_spawn(&mut self) {
         let id = self.id;
-        self.results = AsyncResource::new(move || atletiek_nu_api::get_athlete_event_result(id));
+        self.results = AsyncResource::new(move || Ok(atletiek_nu_api::blocking::get_athlete_event_result(id)?));
     }
 
     fn draw(&mut self, ui: &mut Ui, ctx: &mut dyn AppCtx) {
@@ -46,43 +47,52 @@ impl Window for EventResultsWindow {
                 ui.centered_and_justified(|ui| ui.label(RichText::new(e).color(Color32::RED)));
             }
             AsyncResource::Finished(r) => {
-                let mut results: BTreeMap<String, Vec<(f64, Option<f64>)>> = BTreeMap::new();
-                for i in &r.results {
-                    if let Some(res) = results.get_mut(&i.event_name) {
-                        res.push((i.result, i.wind_speed));
-                    } else {
-                        results.insert(i.event_name.to_owned(), vec![(i.result, i.wind_speed)]);
-                    }
-                }
-
                 ui.label(format!("Name:           {}", self.name));
                 ui.label(format!("Competition:    {}", self.competition_name));
                 ui.label(format!("Participant ID: {}", self.id));
 
-                for (name, results) in results.iter() {
+                for result in &r.results {
                     ui.add_space(20.0);
-                    ui.strong(name);
-                    for (result, wind) in results {
-                        let result_text = if result.is_nan() {
-                            "x".to_string()
-                        } else {
-                            format!("{:.2}", result)
-                        };
+                    let mut title = result.event_name.clone();
+                    if let Some(round) = &result.round {
+                        title = format!("{} - {}", title, round.name);
+                    }
+                    if let Some(event) = &result.timetable_event {
+                        title = format!("{} ({})", title, event.time.with_timezone(&TIMEZONE).format("%d-%m-%Y %H:%M"));
+                    }
+                    ui.strong(title);
+
+                    for item in &result.items {
+                        match item {
+                            EventResultItem::Measurement { result, wind_speed, status, .. } => {
+                                let result_text = match (result, status) {
+                                    (_, Some(status)) => status.to_string(),
+                                    (Some(result), None) => result.to_string(),
+                                    (None, None) => "-".to_string(),
+                                };
 
-                        let (text, valid) = if let Some(wind) = wind {
-                            (
-                                format!("{} ({:.1} m/s wind)", result_text, wind),
-                                (wind <= &2.0 && wind >= &-2.0),
-                            )
-                        } else {
-                            (result_text, true)
-                        };
+                                let (text, valid) = if let Some(wind) = wind_speed {
+                                    (
+                                        format!("{} ({:.1} m/s wind)", result_text, wind),
+                                        (wind <= &2.0 && wind >= &-2.0),
+                                    )
+                                } else {
+                                    (result_text, true)
+                                };
 
-                        ui.label(RichText::new(text).color(if !valid {
-                            Color32::RED
-                        } else {
-                            Color32::WHITE
-                        }));
+                                ui.label(RichText::new(text).color(if !valid {
+                                    Color32::RED
+                                } else {
+                                    Color32::WHITE
+                                }));
+                            }
+                            EventResultItem::Position { position } => {
+                                ui.label(format!("Position {}", position));
+                            }
+                            EventResultItem::Points { amount } => {
+                                ui.label(format!("{} points", amount));
+                            }
+                        }
                     }
                 }
             }
@@ -39,7 +39,7 @@ impl Window for RegistrationsWindow {
     fn on_spawn(&mut self) {
         let id = self.competition_id;
         self.registrations =
-            AsyncResource::new(move || atletiek_nu_api::get_competition_registrations(&id))
+            AsyncResource::new(move || Ok(atletiek_nu_api::blocking::get_competition_registrations(&id)?))
     }
 
     fn draw(&mut self, ui: &mut Ui, ctx: &mut dyn AppCtx) {
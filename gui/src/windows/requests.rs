@@ -20,7 +20,7 @@ impl Window for RequestsWindow {
             .striped(true)
             .resizable(true)
             .cell_layout(Layout::left_to_right(Align::Center))
-            .columns(Column::auto().resizable(true), 4);
+            .columns(Column::auto().resizable(true), 5);
 
         table
             .header(20.0, |mut header| {
@@ -30,6 +30,9 @@ impl Window for RequestsWindow {
                 header.col(|ui| {
                     ui.strong("URL");
                 });
+                header.col(|ui| {
+                    ui.strong("Attempt");
+                });
                 header.col(|ui| {
                     ui.strong("Status");
                 });
@@ -40,22 +43,32 @@ impl Window for RequestsWindow {
             .body(|body| {
                 body.rows(20.0, ctx.get_requests().len(), |index, mut row| {
                     let request = ctx.get_requests().get(&index).unwrap();
-                    let (id, url, status, duration) = match request {
-                        RequestState::Pending { req, started_at } => (
+                    let (id, url, attempt, status, duration) = match request {
+                        RequestState::Pending { url, attempt, started_at } => (
                             index,
-                            req.url().as_str(),
-                            "Pending",
-                            started_at.elapsed().as_millis(),
+                            url.as_str(),
+                            *attempt,
+                            "Pending".to_string(),
+                            started_at.elapsed().as_millis().to_string(),
                         ),
                         RequestState::Finished {
-                            req,
+                            url,
+                            attempt,
                             code,
                             duration,
                         } => (
                             index,
-                            req.url().as_str(),
-                            code.as_str(),
-                            duration.as_millis(),
+                            url.as_str(),
+                            *attempt,
+                            code.as_str().to_string(),
+                            duration.as_millis().to_string(),
+                        ),
+                        RequestState::Failed { url, attempt, error } => (
+                            index,
+                            url.as_str(),
+                            *attempt,
+                            error.to_owned(),
+                            String::new(),
                         ),
                     };
 
@@ -65,11 +78,14 @@ impl Window for RequestsWindow {
                     row.col(|ui| {
                         ui.label(url);
                     });
+                    row.col(|ui| {
+                        ui.label(attempt.to_string());
+                    });
                     row.col(|ui| {
                         ui.label(status);
                     });
                     row.col(|ui| {
-                        ui.label(duration.to_string());
+                        ui.label(duration);
                     });
                 })
             })
@@ -41,7 +41,7 @@ impl Window for AthleteSearchWindow {
                 if ui.button("Search").clicked() || pressed_enter {
                     let query = self.search_field.clone();
                     self.search =
-                        AsyncResource::new(move || atletiek_nu_api::search_athletes(&query));
+                        AsyncResource::new(move || Ok(atletiek_nu_api::blocking::search_athletes(&query)?));
                 }
             }
         });
@@ -3,8 +3,9 @@ use crate::async_resource::AsyncResource;
 use crate::windows::registrations::RegistrationsWindow;
 use atletiek_nu_api::chrono::{self, Months, NaiveDate};
 use atletiek_nu_api::models::competitions_list_web::CompetitionsWebList;
+use atletiek_nu_api::{CompetitionKind, CompetitionSearch, Country};
 use eframe::emath::Align;
-use egui::{Color32, Key, Layout, RichText, TextEdit, Ui};
+use egui::{Color32, ComboBox, Key, Layout, RichText, TextEdit, Ui};
 use egui_datepicker::DatePicker;
 use egui_extras::{Column, TableBuilder};
 
@@ -13,6 +14,9 @@ pub struct SearchCompetitionsWindow {
     search_field: String,
     start: NaiveDate,
     end: NaiveDate,
+    country: Country,
+    kinds: Vec<CompetitionKind>,
+    results_only: bool,
 }
 
 impl Default for SearchCompetitionsWindow {
@@ -24,6 +28,9 @@ impl Default for SearchCompetitionsWindow {
             search_field: String::new(),
             start: now,
             end: now.checked_add_months(Months::new(1)).unwrap(),
+            country: Country::Netherlands,
+            kinds: vec![CompetitionKind::Indoor, CompetitionKind::Outdoor],
+            results_only: false,
         }
     }
 }
@@ -50,16 +57,40 @@ impl Window for SearchCompetitionsWindow {
             ui.add_space(10.0);
             ui.label("End");
             ui.add(DatePicker::new("end-picker", &mut self.end));
+            ui.add_space(10.0);
+            ComboBox::from_id_source("country-picker")
+                .selected_text(self.country.name())
+                .show_ui(ui, |ui| {
+                    for country in Country::supported() {
+                        ui.selectable_value(&mut self.country, country.clone(), country.name());
+                    }
+                });
+            ui.add_space(10.0);
+            for kind in CompetitionKind::ALL {
+                let mut checked = self.kinds.contains(&kind);
+                if ui.checkbox(&mut checked, format!("{:?}", kind)).changed() {
+                    match checked {
+                        true => self.kinds.push(kind),
+                        false => self.kinds.retain(|v| *v != kind),
+                    }
+                }
+            }
+            ui.checkbox(&mut self.results_only, "With results");
 
             if self.search.is_pending() {
                 ui.spinner();
             } else {
                 if ui.button("Search").clicked() || pressed_enter {
-                    let query = self.search_field.clone();
-                    let start = self.start.clone();
-                    let end = self.end.clone();
+                    let search = CompetitionSearch::new(self.start, self.end)
+                        .query(&self.search_field)
+                        .country(self.country.clone())
+                        .kinds(self.kinds.iter().copied());
+                    let search = match self.results_only {
+                        true => search.results_available(true),
+                        false => search,
+                    };
                     self.search = AsyncResource::new(move || {
-                        atletiek_nu_api::search_competitions_for_time_period(start, end, &query)
+                        Ok(atletiek_nu_api::blocking::search_competitions_with(&search)?)
                     });
                 }
             }
@@ -0,0 +1,279 @@
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::io;
+use std::net::{SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use hyper::header::{CONTENT_TYPE, RETRY_AFTER};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use log::{info, trace, warn};
+use tokio::sync::oneshot;
+
+// Maps request paths to fixture files, one `<path> <file>` per line
+const ROUTES_FILE: &str = "routes.txt";
+
+/// Something that goes wrong while serving a page
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Waits before answering
+    Delay(Duration),
+    /// Answers with this status code and an empty body
+    Status(u16),
+    /// Answers 429 with a Retry-After header (in seconds)
+    RateLimited { retry_after: u64 },
+    /// Serves only the first half of the page
+    MalformedHtml,
+}
+
+#[derive(Debug, Clone)]
+struct Route {
+    path: String,
+    query: Vec<(String, String)>,
+    file: String,
+}
+
+struct State {
+    dir: PathBuf,
+    routes: Mutex<Vec<Route>>,
+    faults: Mutex<Vec<Fault>>,
+    next_faults: Mutex<VecDeque<Fault>>,
+    requests: Mutex<Vec<String>>,
+}
+
+/// Serves fixture pages on the paths that athletics.app uses, until it's dropped
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<State>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    /// Starts a server on a random local port, must be called from within a tokio runtime
+    pub async fn start<P: Into<PathBuf>>(dir: P) -> io::Result<Self> {
+        Self::start_on(SocketAddr::from(([127, 0, 0, 1], 0)), dir).await
+    }
+
+    pub async fn start_on<P: Into<PathBuf>>(addr: SocketAddr, dir: P) -> io::Result<Self> {
+        let dir = dir.into();
+        let routes = load_routes(&dir)?;
+        let state = Arc::new(State {
+            dir,
+            routes: Mutex::new(routes),
+            faults: Mutex::new(Vec::new()),
+            next_faults: Mutex::new(VecDeque::new()),
+            requests: Mutex::new(Vec::new()),
+        });
+
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let service_state = Arc::clone(&state);
+        let make_service = make_service_fn(move |_| {
+            let state = Arc::clone(&service_state);
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req| handle(Arc::clone(&state), req)))
+            }
+        });
+
+        let (shutdown, shutdown_rx) = oneshot::channel::<()>();
+        let server = Server::from_tcp(listener)
+            .map_err(io::Error::other)?
+            .serve(make_service)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            });
+
+        tokio::spawn(async move {
+            if let Err(e) = server.await {
+                warn!("Mock server stopped: {}", e);
+            }
+        });
+
+        info!("Mock server listening on {}", addr);
+
+        Ok(Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The base url to point the client at
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Serves `file` from the fixture directory for `path`. A query in the path has to match as well,
+    /// but the request may have more parameters.
+    pub fn route<T: ToString>(&self, path: T, file: T) {
+        let route = parse_route(&path.to_string(), &file.to_string());
+        self.state.routes.lock().unwrap().insert(0, route);
+    }
+
+    /// Applies the fault to every request until the faults are cleared
+    pub fn fault(&self, fault: Fault) {
+        self.state.faults.lock().unwrap().push(fault);
+    }
+
+    /// Applies the fault to the next request only, multiple calls queue up
+    pub fn fault_next(&self, fault: Fault) {
+        self.state.next_faults.lock().unwrap().push_back(fault);
+    }
+
+    pub fn clear_faults(&self) {
+        self.state.faults.lock().unwrap().clear();
+        self.state.next_faults.lock().unwrap().clear();
+    }
+
+    /// The path and query of every request received so far
+    pub fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+async fn handle(state: Arc<State>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let path_and_query = req.uri().path_and_query().map(|v| v.as_str()).unwrap_or("/").to_string();
+    trace!("Mock server got request for {}", path_and_query);
+    state.requests.lock().unwrap().push(path_and_query);
+
+    let mut faults = state.faults.lock().unwrap().clone();
+    faults.extend(state.next_faults.lock().unwrap().pop_front());
+
+    for fault in faults.iter() {
+        if let Fault::Delay(delay) = fault {
+            tokio::time::sleep(*delay).await;
+        }
+    }
+
+    for fault in faults.iter() {
+        match fault {
+            Fault::Status(status) => {
+                let status = StatusCode::from_u16(*status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+                return Ok(response(status, Body::empty()));
+            },
+            Fault::RateLimited { retry_after } => {
+                let mut res = response(StatusCode::TOO_MANY_REQUESTS, Body::empty());
+                res.headers_mut().insert(RETRY_AFTER, retry_after.to_string().parse().unwrap());
+                return Ok(res);
+            },
+            _ => (),
+        }
+    }
+
+    let file = {
+        let routes = state.routes.lock().unwrap();
+        routes.iter().find(|v| matches(v, req.uri().path(), req.uri().query())).map(|v| v.file.clone())
+    };
+
+    let body = match file {
+        Some(file) => match tokio::fs::read_to_string(state.dir.join(&file)).await {
+            Ok(v) => v,
+            Err(e) => {
+                warn!("Failed to read fixture {}: {}", file, e);
+                return Ok(response(StatusCode::INTERNAL_SERVER_ERROR, Body::empty()));
+            }
+        },
+        None => return Ok(response(StatusCode::NOT_FOUND, Body::from("Not found"))),
+    };
+
+    let body = if faults.contains(&Fault::MalformedHtml) {
+        truncate_half(&body).to_string()
+    } else {
+        body
+    };
+
+    let mut res = response(StatusCode::OK, Body::from(body));
+    res.headers_mut().insert(CONTENT_TYPE, "text/html; charset=utf-8".parse().unwrap());
+    Ok(res)
+}
+
+fn response(status: StatusCode, body: Body) -> Response<Body> {
+    let mut res = Response::new(body);
+    *res.status_mut() = status;
+    res
+}
+
+fn matches(route: &Route, path: &str, query: Option<&str>) -> bool {
+    if route.path != path {
+        return false;
+    }
+
+    let query = parse_query(query.unwrap_or_default());
+    route.query.iter().all(|v| query.contains(v))
+}
+
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|v| !v.is_empty())
+        .map(|v| match v.split_once('=') {
+            Some((k, v)) => (k.to_string(), v.to_string()),
+            None => (v.to_string(), String::new()),
+        })
+        .collect()
+}
+
+fn parse_route(path: &str, file: &str) -> Route {
+    let (path, query) = path.split_once('?').unwrap_or((path, ""));
+
+    Route {
+        path: path.to_string(),
+        query: parse_query(query),
+        file: file.to_string(),
+    }
+}
+
+fn load_routes(dir: &Path) -> io::Result<Vec<Route>> {
+    let text = match std::fs::read_to_string(dir.join(ROUTES_FILE)) {
+        Ok(v) => v,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let mut routes = Vec::new();
+    for line in text.lines().map(|v| v.trim()).filter(|v| !v.is_empty() && !v.starts_with('#')) {
+        match line.split_once(char::is_whitespace) {
+            Some((path, file)) => routes.push(parse_route(path, file.trim())),
+            None => warn!("Ignoring route without a file: {}", line),
+        }
+    }
+
+    Ok(routes)
+}
+
+fn truncate_half(body: &str) -> &str {
+    let mut idx = body.len() / 2;
+    while !body.is_char_boundary(idx) {
+        idx -= 1;
+    }
+
+    &body[..idx]
+}
+
+#[test]
+fn test_route_matching() {
+    let route = parse_route("/athleteapp.php?page=athletes&do=searchresults", "athlete_search.html");
+
+    assert!(matches(&route, "/athleteapp.php", Some("page=athletes&do=searchresults&name=femke&language=en_GB")));
+    assert!(!matches(&route, "/athleteapp.php", Some("page=events&do=searchresults")));
+    assert!(!matches(&route, "/feeder.php", Some("page=athletes&do=searchresults")));
+
+    let route = parse_route("/atleet/profiel/921275", "profile_implements.html");
+    assert!(matches(&route, "/atleet/profiel/921275", None));
+    assert!(!matches(&route, "/atleet/profiel/9212750", None));
+}
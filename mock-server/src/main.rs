@@ -0,0 +1,66 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+use mock_server::{Fault, MockServer};
+
+const DEFAULT_PORT: u16 = 8000;
+const DEFAULT_DIR: &str = "atletiek-nu-api/test-data";
+const USAGE: &str = "Usage: mock-server [--port <port>] [--dir <fixture dir>] [--route <path> <file>]... [--delay-ms <ms>] [--status <code>] [--malformed]";
+
+#[tokio::main]
+async fn main() {
+    pretty_env_logger::init();
+
+    let mut port = DEFAULT_PORT;
+    let mut dir = DEFAULT_DIR.to_string();
+    let mut routes = Vec::new();
+    let mut faults = Vec::new();
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().unwrap_or_else(|| exit_with_usage(&format!("Missing value for {}", arg)));
+        match arg.as_str() {
+            "--port" => port = value().parse().unwrap_or_else(|_| exit_with_usage("Invalid port")),
+            "--dir" => dir = value(),
+            "--route" => {
+                let path = value();
+                routes.push((path, value()));
+            },
+            "--delay-ms" => faults.push(Fault::Delay(Duration::from_millis(value().parse().unwrap_or_else(|_| exit_with_usage("Invalid delay"))))),
+            "--status" => faults.push(Fault::Status(value().parse().unwrap_or_else(|_| exit_with_usage("Invalid status code")))),
+            "--malformed" => faults.push(Fault::MalformedHtml),
+            "--help" => {
+                println!("{}", USAGE);
+                return;
+            },
+            other => exit_with_usage(&format!("Unknown argument {}", other)),
+        }
+    }
+
+    let server = match MockServer::start_on(SocketAddr::from(([127, 0, 0, 1], port)), &dir).await {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Failed to start mock server: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    for (path, file) in routes {
+        server.route(path, file);
+    }
+
+    for fault in faults {
+        server.fault(fault);
+    }
+
+    println!("Serving {} on {}", dir, server.url());
+    println!("Point a client at it with AtnClient::builder().base_url(\"{}\")", server.url());
+
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        eprintln!("Failed to wait for ctrl-c: {}", e);
+    }
+}
+
+fn exit_with_usage(msg: &str) -> ! {
+    eprintln!("{}\n{}", msg, USAGE);
+    std::process::exit(2);
+}